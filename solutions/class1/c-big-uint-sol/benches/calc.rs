@@ -0,0 +1,209 @@
+//! Timings for the calculator's typical workloads.
+//!
+//! ```sh
+//! cargo bench -p c-big-uint-sol
+//! ```
+//!
+//! Most values here fit in one or two limbs, which is where inline limb
+//! storage matters. The last two workloads use large numbers for comparison.
+//!
+//! Some workloads are followed by a `vs baseline` line timed in the same run:
+//! the same arithmetic on `Vec<u64>` limbs, allocated for every result as
+//! `BigUInt` did before it stored small values inline, and decimal printing
+//! by repeated division, one 19-digit chunk at a time. The ratio is the
+//! baseline's time over the new one; it is what to compare across machines,
+//! since the absolute numbers are not.
+
+use std::hint::black_box;
+use std::str::FromStr;
+use std::time::Instant;
+
+use c_big_uint_sol::big_uint::BigUInt;
+use c_big_uint_sol::expr::Env;
+
+/// Nanoseconds per call of `f`, after a warm-up.
+fn time(iters: u32, mut f: impl FnMut()) -> u128 {
+    for _ in 0..iters / 10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..iters {
+        f();
+    }
+    start.elapsed().as_nanos() / iters as u128
+}
+
+fn bench(name: &str, iters: u32, f: impl FnMut()) {
+    let ns = time(iters, f);
+    println!("{name:<24} {ns:>10} ns/iter");
+}
+
+/// Times `f` and then `baseline`, and how many times faster `f` is.
+fn compare(name: &str, iters: u32, f: impl FnMut(), baseline: impl FnMut()) {
+    let ns = time(iters, f);
+    let base = time(iters, baseline);
+    let ratio = base as f64 / ns.max(1) as f64;
+
+    println!("{name:<24} {ns:>10} ns/iter");
+    println!("{:<24} {base:>10} ns/iter  ({ratio:.2}x)", "  vs baseline");
+}
+
+/// `a + b` on heap limbs.
+fn vec_add(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(a.len() + 1);
+    let mut carry = false;
+
+    for (i, &x) in a.iter().enumerate() {
+        let (s, c1) = x.overflowing_add(b.get(i).copied().unwrap_or(0));
+        let (s, c2) = s.overflowing_add(carry as u64);
+        out.push(s);
+        carry = c1 || c2;
+    }
+    if carry {
+        out.push(1);
+    }
+    out
+}
+
+/// `a * b` on heap limbs, schoolbook.
+fn vec_mul(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut out = vec![0; a.len() + b.len()];
+
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u128 * y as u128 + out[i + j] as u128 + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        out[i + b.len()] = carry as u64;
+    }
+    while out.last() == Some(&0) {
+        out.pop();
+    }
+    out
+}
+
+/// Quotient and remainder of `a / d` on heap limbs.
+fn vec_div_rem(a: &[u64], d: u64) -> (Vec<u64>, Vec<u64>) {
+    let mut q = vec![0; a.len()];
+    let mut r = 0u128;
+
+    for (i, &x) in a.iter().enumerate().rev() {
+        let t = r << 64 | x as u128;
+        q[i] = (t / d as u128) as u64;
+        r = t % d as u128;
+    }
+    while q.last() == Some(&0) {
+        q.pop();
+    }
+    (q, vec![r as u64])
+}
+
+/// Decimal digits of `a` by repeated division: quadratic in the length.
+fn naive_to_string(a: &BigUInt) -> String {
+    let chunk = BigUInt::from(10_000_000_000_000_000_000u64);
+    let mut parts = Vec::new();
+    let mut a = a.clone();
+
+    while a >= chunk {
+        let (q, r) = a.checked_div_rem(&chunk).unwrap();
+        parts.push(r.to_string());
+        a = q;
+    }
+
+    let mut s = a.to_string();
+    for part in parts.iter().rev() {
+        s.push_str(&format!("{part:0>19}"));
+    }
+    s
+}
+
+fn main() {
+    let lines = [
+        "100 + 100",
+        "100 - 250",
+        "123456789 * 987654321",
+        "18446744073709551615 + 1",
+        "-7 / 2",
+        "99999999999 % 12345",
+        "340282366920938463463374607431768211455 < 1",
+    ];
+    bench("calculator lines", 20_000, || {
+        let mut env = Env::new();
+        for line in lines {
+            black_box(env.eval_line(black_box(line)).unwrap().to_string());
+        }
+    });
+
+    compare(
+        "small add and mul",
+        20_000,
+        || {
+            let mut acc = BigUInt::zero();
+            for i in 1..100u64 {
+                let x = BigUInt::from(i);
+                acc = &acc + &(&x * &x);
+            }
+            black_box(acc);
+        },
+        || {
+            let mut acc = Vec::new();
+            for i in 1..100u64 {
+                let x = vec![black_box(i)];
+                acc = vec_add(&acc, &vec_mul(&x, &x));
+            }
+            black_box(acc);
+        },
+    );
+
+    let m = BigUInt::from_str("1267650600228229401496703205653").unwrap();
+    bench("two-limb fibonacci mod", 20_000, || {
+        let (mut a, mut b) = (BigUInt::zero(), BigUInt::one());
+        for _ in 0..100 {
+            let c = &(&a + &b) % &m;
+            a = b;
+            b = c;
+        }
+        black_box(b);
+    });
+
+    compare(
+        "small div and rem",
+        20_000,
+        || {
+            let n = BigUInt::from(u64::MAX);
+            for i in 1..100u64 {
+                let d = BigUInt::from(i);
+                black_box((&n / &d, &n % &d));
+            }
+        },
+        || {
+            let n = vec![u64::MAX];
+            for i in 1..100u64 {
+                let d = black_box(i);
+                black_box((vec_div_rem(&n, d).0, vec_div_rem(&n, d).1));
+            }
+        },
+    );
+
+    let big = BigUInt::from_str(&"1234567890".repeat(100)).unwrap();
+    bench("1000-digit mul and print", 2_000, || {
+        black_box((&big * &big).to_string());
+    });
+
+    let huge = BigUInt::from_str(&"1234567890".repeat(10_000)).unwrap();
+    assert_eq!(naive_to_string(&huge), huge.to_string());
+    compare(
+        "100000-digit print",
+        5,
+        || {
+            black_box(huge.to_string());
+        },
+        || {
+            black_box(naive_to_string(&huge));
+        },
+    );
+}
@@ -0,0 +1,518 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops;
+use std::str::FromStr;
+
+use crate::big_int::BigInt;
+use crate::big_uint::{div_rem, BigUInt, ParseBigUIntError, ParseOptions};
+
+/// Digits after the point kept by the `/` operator when both operands have fewer.
+pub const DEFAULT_DIV_SCALE: usize = 20;
+
+/// How to round when digits have to be dropped.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RoundingMode {
+    /// To the nearest value; ties go to the even neighbour.
+    HalfEven,
+    /// To the nearest value; ties go away from zero.
+    HalfUp,
+    /// Toward negative infinity.
+    Floor,
+    /// Toward positive infinity.
+    Ceiling,
+    /// Toward zero.
+    Truncate,
+}
+
+/// Big decimal number module
+///
+/// ## Example
+///
+/// ```
+/// let a = BigDecimal::from_str("12345.6789").unwrap();
+/// let b = BigDecimal::from_str("0.01").unwrap();
+/// assert_eq!((&a * &b).to_string(), "123.456789");
+/// assert_eq!(a.round(2, RoundingMode::HalfEven).to_string(), "12345.68");
+/// ```
+///
+/// ## Implementation
+///
+/// The number is `value × 10^-scale`, where `value` is a `BigInt`.
+/// The scale is the number of digits after the point, and is kept as written:
+/// `1.50` and `1.5` compare equal but print differently.
+#[derive(Debug, Clone)]
+pub struct BigDecimal {
+    value: BigInt,
+    scale: usize,
+}
+
+fn pow10(n: usize) -> BigUInt {
+    BigUInt::from(10u64).pow(n as u32)
+}
+
+/// Rounds the quotient `q` with remainder `r` of a division by `d`,
+/// where the exact quotient has the sign given by `neg`.
+fn round_quotient(neg: bool, q: BigUInt, r: &BigUInt, d: &BigUInt, mode: RoundingMode) -> BigInt {
+    let up = if r.is_zero() {
+        false
+    } else {
+        match mode {
+            RoundingMode::Truncate => false,
+            RoundingMode::Floor => neg,
+            RoundingMode::Ceiling => !neg,
+            RoundingMode::HalfUp => &(r + r) >= d,
+            RoundingMode::HalfEven => match (r + r).cmp(d) {
+                Ordering::Less => false,
+                Ordering::Equal => q.bit(0),
+                Ordering::Greater => true,
+            },
+        }
+    };
+
+    let q = if up { &q + &BigUInt::one() } else { q };
+    BigInt::from_parts(neg, q)
+}
+
+impl BigDecimal {
+    pub fn zero() -> BigDecimal {
+        BigDecimal {
+            value: BigInt::zero(),
+            scale: 0,
+        }
+    }
+
+    /// `value × 10^-scale`
+    pub fn new(value: BigInt, scale: usize) -> BigDecimal {
+        BigDecimal { value, scale }
+    }
+
+    /// Digits after the point.
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    /// The unscaled integer value.
+    pub fn unscaled(&self) -> &BigInt {
+        &self.value
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.value.is_negative()
+    }
+
+    /// Same number with exactly `scale` digits after the point,
+    /// rounded with `mode` if digits are dropped.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigDecimal::from_str("-2.5").unwrap();
+    /// assert_eq!(a.round(0, RoundingMode::HalfEven).to_string(), "-2");
+    /// assert_eq!(a.round(0, RoundingMode::HalfUp).to_string(), "-3");
+    /// assert_eq!(a.round(3, RoundingMode::Floor).to_string(), "-2.500");
+    /// ```
+    pub fn round(&self, scale: usize, mode: RoundingMode) -> BigDecimal {
+        if scale >= self.scale {
+            let value = &self.value * &BigInt::from(pow10(scale - self.scale));
+            return BigDecimal { value, scale };
+        }
+
+        let d = pow10(self.scale - scale);
+        let (q, r) = div_rem(self.value.magnitude(), &d);
+
+        BigDecimal {
+            value: round_quotient(self.value.is_negative(), q, &r, &d, mode),
+            scale,
+        }
+    }
+
+    /// `self / rhs` with `scale` digits after the point, rounded with `mode`.
+    /// If rhs is `0` it should panic as `division by zero`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigDecimal::from_str("2").unwrap();
+    /// let b = BigDecimal::from_str("3").unwrap();
+    /// assert_eq!(a.div_with(&b, 4, RoundingMode::Floor).to_string(), "0.6666");
+    /// ```
+    pub fn div_with(&self, rhs: &BigDecimal, scale: usize, mode: RoundingMode) -> BigDecimal {
+        if rhs.is_zero() {
+            panic!("division by zero");
+        }
+
+        // (v1 / 10^s1) / (v2 / 10^s2) × 10^scale = v1 × 10^(s2 + scale) / (v2 × 10^s1)
+        let n = self.value.magnitude() * &pow10(rhs.scale + scale);
+        let d = rhs.value.magnitude() * &pow10(self.scale);
+        let (q, r) = div_rem(&n, &d);
+        let neg = self.value.is_negative() != rhs.value.is_negative();
+
+        BigDecimal {
+            value: round_quotient(neg, q, &r, &d, mode),
+            scale,
+        }
+    }
+
+    /// Integer part after rounding with `mode`, or `None` if it is negative.
+    pub fn to_big_uint(&self, mode: RoundingMode) -> Option<BigUInt> {
+        BigUInt::try_from(self.round(0, mode).value).ok()
+    }
+
+    /// Both numbers with the larger of the two scales.
+    fn align(&self, other: &BigDecimal) -> (BigInt, BigInt, usize) {
+        let scale = self.scale.max(other.scale);
+        let a = self.round(scale, RoundingMode::Truncate).value;
+        let b = other.round(scale, RoundingMode::Truncate).value;
+        (a, b, scale)
+    }
+}
+
+impl FromStr for BigDecimal {
+    type Err = ParseBigUIntError;
+
+    /// String to decimal number, like `12345.6789` or `-0.5`.
+    /// The scale is the number of digits written after the point.
+    /// Radix prefixes like `0x` are not accepted.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigDecimal::from_str("-1,234.50").unwrap();
+    /// assert_eq!(a.scale(), 2);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let decimal = ParseOptions::new().radix_prefix(false);
+
+        match s.split_once('.') {
+            Some((int, frac)) => {
+                let point = int.len();
+                let int = BigInt::parse_with(int, &decimal)?;
+                let scale = frac.chars().filter(|c| c.is_ascii_digit()).count();
+                let frac = BigUInt::from_str_radix(frac, 10).map_err(|e| e.shifted(point + 1))?;
+
+                let mag = &(int.magnitude() * &pow10(scale)) + &frac;
+                let value = BigInt::from_parts(s.starts_with('-'), mag);
+                Ok(BigDecimal { value, scale })
+            }
+            None => Ok(BigDecimal::from(BigInt::parse_with(s, &decimal)?)),
+        }
+    }
+}
+
+impl<'a, T> ops::Add<T> for &BigDecimal
+where
+    T: Into<&'a BigDecimal>,
+{
+    type Output = BigDecimal;
+
+    /// Add operator between two numbers. The result has the larger scale.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigDecimal::from_str("1.5").unwrap();
+    /// let b = BigDecimal::from_str("0.25").unwrap();
+    ///
+    /// assert_eq!((&a + &b).to_string(), "1.75");
+    /// ```
+    fn add(self, rhs: T) -> Self::Output {
+        let (a, b, scale) = self.align(rhs.into());
+        BigDecimal::new(&a + &b, scale)
+    }
+}
+
+impl<'a, T> ops::Sub<T> for &BigDecimal
+where
+    T: Into<&'a BigDecimal>,
+{
+    type Output = BigDecimal;
+
+    /// Sub operator between two numbers. The result has the larger scale.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigDecimal::from_str("1.5").unwrap();
+    /// let b = BigDecimal::from_str("2.25").unwrap();
+    ///
+    /// assert_eq!((&a - &b).to_string(), "-0.75");
+    /// ```
+    fn sub(self, rhs: T) -> Self::Output {
+        let (a, b, scale) = self.align(rhs.into());
+        BigDecimal::new(&a - &b, scale)
+    }
+}
+
+impl<'a, T> ops::Mul<T> for &BigDecimal
+where
+    T: Into<&'a BigDecimal>,
+{
+    type Output = BigDecimal;
+
+    /// Mul operator between two numbers. The product is exact,
+    /// so its scale is the sum of both scales.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigDecimal::from_str("1.5").unwrap();
+    /// let b = BigDecimal::from_str("-0.25").unwrap();
+    ///
+    /// assert_eq!((&a * &b).to_string(), "-0.375");
+    /// ```
+    fn mul(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        BigDecimal::new(&self.value * &rhs.value, self.scale + rhs.scale)
+    }
+}
+
+impl<'a, T> ops::Div<T> for &BigDecimal
+where
+    T: Into<&'a BigDecimal>,
+{
+    type Output = BigDecimal;
+
+    /// Div operator between two numbers, rounded half to even.
+    /// The result keeps the largest of both scales and `DEFAULT_DIV_SCALE`;
+    /// use `div_with` to choose the scale and rounding.
+    /// If rhs is `0` it should panic as `division by zero`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigDecimal::from_str("1").unwrap();
+    /// let b = BigDecimal::from_str("8").unwrap();
+    ///
+    /// assert_eq!(&a / &b, BigDecimal::from_str("0.125").unwrap());
+    /// ```
+    fn div(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        let scale = self.scale.max(rhs.scale).max(DEFAULT_DIV_SCALE);
+        self.div_with(rhs, scale, RoundingMode::HalfEven)
+    }
+}
+
+impl ops::Neg for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn neg(self) -> Self::Output {
+        BigDecimal::new(-&self.value, self.scale)
+    }
+}
+
+impl PartialEq for BigDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigDecimal {}
+
+impl PartialOrd for BigDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigDecimal {
+    /// Comparison operator between two numbers. The scale does not matter.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let a = BigDecimal::from_str("1.50").unwrap();
+    /// let b = BigDecimal::from_str("1.5").unwrap();
+    ///
+    /// assert_eq!(a, b);
+    /// ```
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b, _) = self.align(other);
+        a.cmp(&b)
+    }
+}
+
+impl Display for BigDecimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.value.is_negative() {
+            write!(f, "-")?;
+        }
+
+        let s = self.value.magnitude().to_string();
+        if self.scale == 0 {
+            return write!(f, "{s}");
+        }
+
+        let s = format!("{s:0>width$}", width = self.scale + 1);
+        let (int, frac) = s.split_at(s.len() - self.scale);
+        write!(f, "{int}.{frac}")
+    }
+}
+
+impl From<BigInt> for BigDecimal {
+    fn from(x: BigInt) -> Self {
+        BigDecimal::new(x, 0)
+    }
+}
+
+impl From<BigUInt> for BigDecimal {
+    fn from(x: BigUInt) -> Self {
+        BigDecimal::new(BigInt::from(x), 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{BigDecimal, RoundingMode};
+    use crate::big_uint::BigUInt;
+
+    #[test]
+    fn string_test() {
+        fn case(s: &str, c: &str) {
+            assert_eq!(BigDecimal::from_str(s).unwrap().to_string(), c, "{s}");
+        }
+
+        case("0", "0");
+        case("12345.6789", "12345.6789");
+        case("-0.5", "-0.5");
+        case("0.000", "0.000");
+        case("-0.000", "0.000");
+        case("1,234.50", "1234.50");
+        case("+7.07", "7.07");
+
+        assert!(BigDecimal::from_str("").is_err());
+        assert!(BigDecimal::from_str(".5").is_err());
+        assert!(BigDecimal::from_str("1.").is_err());
+        assert!(BigDecimal::from_str("1.-5").is_err());
+        assert!(BigDecimal::from_str("1.2.3").is_err());
+        assert!(BigDecimal::from_str("0x10.5").is_err());
+        assert!(BigDecimal::from_str("-0b1.1").is_err());
+        assert!(BigDecimal::from_str("0o17").is_err());
+    }
+
+    #[test]
+    fn arith_test() {
+        fn case(a: &str, b: &str, sum: &str, diff: &str, prod: &str) {
+            let a = BigDecimal::from_str(a).unwrap();
+            let b = BigDecimal::from_str(b).unwrap();
+
+            assert_eq!((&a + &b).to_string(), sum);
+            assert_eq!((&a - &b).to_string(), diff);
+            assert_eq!((&a * &b).to_string(), prod);
+        }
+
+        case("1.5", "0.25", "1.75", "1.25", "0.375");
+        case("0.1", "0.2", "0.3", "-0.1", "0.02");
+        case(
+            "-12345.6789",
+            "0.0001",
+            "-12345.6788",
+            "-12345.6790",
+            "-1.23456789",
+        );
+        case("100", "-0.01", "99.99", "100.01", "-1.00");
+    }
+
+    #[test]
+    fn div_test() {
+        fn case(a: &str, b: &str, scale: usize, mode: RoundingMode, c: &str) {
+            let a = BigDecimal::from_str(a).unwrap();
+            let b = BigDecimal::from_str(b).unwrap();
+            assert_eq!(a.div_with(&b, scale, mode).to_string(), c);
+        }
+
+        case("2", "3", 4, RoundingMode::HalfEven, "0.6667");
+        case("2", "3", 4, RoundingMode::Truncate, "0.6666");
+        case("-2", "3", 4, RoundingMode::Floor, "-0.6667");
+        case("-2", "3", 4, RoundingMode::Ceiling, "-0.6666");
+        case("1", "0.08", 1, RoundingMode::HalfEven, "12.5");
+        case("1", "0.08", 0, RoundingMode::HalfEven, "12");
+        case("1", "0.08", 0, RoundingMode::HalfUp, "13");
+        case(
+            "12345.6789",
+            "-0.01",
+            2,
+            RoundingMode::HalfEven,
+            "-1234567.89",
+        );
+
+        let a = BigDecimal::from_str("1").unwrap();
+        let b = BigDecimal::from_str("3").unwrap();
+        assert_eq!((&a / &b).to_string(), "0.33333333333333333333");
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn div_by_zero_test() {
+        let a = BigDecimal::from_str("1.5").unwrap();
+        let _ = &a / &BigDecimal::zero();
+    }
+
+    #[test]
+    fn round_test() {
+        fn case(a: &str, scale: usize, c: [&str; 5]) {
+            let a = BigDecimal::from_str(a).unwrap();
+            let modes = [
+                RoundingMode::HalfEven,
+                RoundingMode::HalfUp,
+                RoundingMode::Floor,
+                RoundingMode::Ceiling,
+                RoundingMode::Truncate,
+            ];
+
+            for (mode, c) in modes.into_iter().zip(c) {
+                assert_eq!(a.round(scale, mode).to_string(), c, "{a} {mode:?}");
+            }
+        }
+
+        case("2.5", 0, ["2", "3", "2", "3", "2"]);
+        case("3.5", 0, ["4", "4", "3", "4", "3"]);
+        case("-2.5", 0, ["-2", "-3", "-3", "-2", "-2"]);
+        case("1.2345", 2, ["1.23", "1.23", "1.23", "1.24", "1.23"]);
+        case("1.2351", 2, ["1.24", "1.24", "1.23", "1.24", "1.23"]);
+        case("-0.001", 2, ["0.00", "0.00", "-0.01", "0.00", "0.00"]);
+        case("7", 2, ["7.00", "7.00", "7.00", "7.00", "7.00"]);
+    }
+
+    #[test]
+    fn convert_test() {
+        let a = BigDecimal::from(BigUInt::from(42u64));
+        assert_eq!(a.to_string(), "42");
+
+        let b = BigDecimal::from_str("42.7").unwrap();
+        assert_eq!(
+            b.to_big_uint(RoundingMode::Truncate),
+            Some(BigUInt::from(42u64))
+        );
+        assert_eq!(
+            b.to_big_uint(RoundingMode::HalfEven),
+            Some(BigUInt::from(43u64))
+        );
+
+        let c = BigDecimal::from_str("-0.4").unwrap();
+        assert_eq!(c.to_big_uint(RoundingMode::HalfEven), Some(BigUInt::zero()));
+        assert_eq!(c.to_big_uint(RoundingMode::Floor), None);
+    }
+
+    #[test]
+    fn cmp_test() {
+        let v =
+            ["-1.5", "-1.49", "0", "0.001", "1.5", "10"].map(|x| BigDecimal::from_str(x).unwrap());
+
+        for (i, a) in v.iter().enumerate() {
+            for (j, b) in v.iter().enumerate() {
+                assert_eq!(a.cmp(b), i.cmp(&j));
+            }
+        }
+
+        assert_eq!(
+            BigDecimal::from_str("1.50").unwrap(),
+            BigDecimal::from_str("1.5").unwrap()
+        );
+    }
+}
@@ -0,0 +1,558 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops;
+use std::str::FromStr;
+
+use crate::big_uint::{div_rem, BigUInt, FormatSpec, ParseBigUIntError, ParseOptions};
+
+/// Big signed integer module
+///
+/// ## Example
+///
+/// ```
+/// let a = BigInt::from(-5i64);
+/// let b = BigInt::from_str("-1000000000000000000000").unwrap();
+/// let c = &a * &b;
+/// ```
+///
+/// ## Implementation
+///
+/// The number is stored as a sign and a `BigUInt` magnitude.
+/// Zero is never negative, so every value has exactly one representation.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BigInt {
+    neg: bool,
+    mag: BigUInt,
+}
+
+impl BigInt {
+    pub const fn zero() -> BigInt {
+        BigInt {
+            neg: false,
+            mag: BigUInt::zero(),
+        }
+    }
+
+    pub fn one() -> BigInt {
+        BigInt {
+            neg: false,
+            mag: BigUInt::one(),
+        }
+    }
+
+    /// Number from a sign and a magnitude. Negative zero becomes zero.
+    pub fn from_parts(neg: bool, mag: BigUInt) -> BigInt {
+        BigInt {
+            neg: neg && !mag.is_zero(),
+            mag,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+
+    pub fn is_positive(&self) -> bool {
+        !self.neg && !self.mag.is_zero()
+    }
+
+    /// Returns `-1`, `0` or `1` by the sign of the number.
+    pub fn signum(&self) -> i8 {
+        if self.neg {
+            -1
+        } else if self.mag.is_zero() {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Absolute value of the number.
+    pub fn magnitude(&self) -> &BigUInt {
+        &self.mag
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from_parts(false, self.mag.clone())
+    }
+
+    /// Decimal string of the number laid out by `spec`. Zero padding goes
+    /// between the sign and the digits.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigInt::from(-1234567i64);
+    /// assert_eq!(a.format_with(FormatSpec::new().separator(',')), "-1,234,567");
+    /// ```
+    pub fn format_with(&self, spec: FormatSpec) -> String {
+        spec.format(self.neg, &self.mag)
+    }
+
+    /// Truncating division and remainder, same as `/` and `%`.
+    /// The remainder has the sign of `self`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let (q, r) = BigInt::from(-7i64).div_rem(&BigInt::from(2i64));
+    /// assert_eq!(q, BigInt::from(-3i64));
+    /// assert_eq!(r, BigInt::from(-1i64));
+    /// ```
+    pub fn div_rem(&self, rhs: &BigInt) -> (BigInt, BigInt) {
+        let (q, r) = div_rem(&self.mag, &rhs.mag);
+        (
+            BigInt::from_parts(self.neg != rhs.neg, q),
+            BigInt::from_parts(self.neg, r),
+        )
+    }
+
+    /// Euclidean division and remainder. The remainder is never negative.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let (q, r) = BigInt::from(-7i64).div_rem_euclid(&BigInt::from(2i64));
+    /// assert_eq!(q, BigInt::from(-4i64));
+    /// assert_eq!(r, BigInt::from(1i64));
+    /// ```
+    pub fn div_rem_euclid(&self, rhs: &BigInt) -> (BigInt, BigInt) {
+        let (q, r) = self.div_rem(rhs);
+
+        if r.neg {
+            let q = if rhs.neg {
+                &q + &BigInt::one()
+            } else {
+                &q - &BigInt::one()
+            };
+            let r = BigInt::from_parts(false, &rhs.mag - &r.mag);
+            (q, r)
+        } else {
+            (q, r)
+        }
+    }
+
+    pub fn div_euclid(&self, rhs: &BigInt) -> BigInt {
+        self.div_rem_euclid(rhs).0
+    }
+
+    pub fn rem_euclid(&self, rhs: &BigInt) -> BigInt {
+        self.div_rem_euclid(rhs).1
+    }
+
+    /// Truncating quotient and remainder, or `None` if `rhs` is `0`.
+    pub fn checked_div_rem(&self, rhs: &BigInt) -> Option<(BigInt, BigInt)> {
+        if rhs.is_zero() {
+            None
+        } else {
+            Some(self.div_rem(rhs))
+        }
+    }
+
+    /// `self / rhs`, or `None` if `rhs` is `0`.
+    pub fn checked_div(&self, rhs: &BigInt) -> Option<BigInt> {
+        self.checked_div_rem(rhs).map(|(q, _)| q)
+    }
+
+    /// `self % rhs`, or `None` if `rhs` is `0`.
+    pub fn checked_rem(&self, rhs: &BigInt) -> Option<BigInt> {
+        self.checked_div_rem(rhs).map(|(_, r)| r)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigUIntError;
+
+    /// String to big integer. It can start with `-` or `+`,
+    /// and the rest follows the rules of `BigUInt::from_str`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let c = BigInt::from_str("-1,000,000,000,000,000,000,000,000");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BigInt::parse_with(s, &ParseOptions::new())
+    }
+}
+
+impl BigInt {
+    /// Like `from_str`, with the digits after the sign read by `opts`.
+    pub(crate) fn parse_with(s: &str, opts: &ParseOptions) -> Result<BigInt, ParseBigUIntError> {
+        let (neg, digits) = if let Some(d) = s.strip_prefix('-') {
+            (true, d)
+        } else if let Some(d) = s.strip_prefix('+') {
+            (false, d)
+        } else {
+            (false, s)
+        };
+
+        let sign = s.len() - digits.len();
+        let mag = opts.parse(digits).map_err(|e| e.shifted(sign))?;
+        Ok(BigInt::from_parts(neg, mag))
+    }
+}
+
+impl<'a, T> ops::Add<T> for &BigInt
+where
+    T: Into<&'a BigInt>,
+{
+    type Output = BigInt;
+
+    /// Add operator between two numbers.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigInt::from(100i64);
+    /// let b = BigInt::from(-150i64);
+    ///
+    /// assert_eq!(&a + &b, BigInt::from(-50i64));
+    /// ```
+    fn add(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        if self.neg == rhs.neg {
+            BigInt::from_parts(self.neg, &self.mag + &rhs.mag)
+        } else if self.mag >= rhs.mag {
+            BigInt::from_parts(self.neg, &self.mag - &rhs.mag)
+        } else {
+            BigInt::from_parts(rhs.neg, &rhs.mag - &self.mag)
+        }
+    }
+}
+
+impl<'a, T> ops::Sub<T> for &BigInt
+where
+    T: Into<&'a BigInt>,
+{
+    type Output = BigInt;
+
+    /// Sub operator between two numbers.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigInt::from(100i64);
+    /// let b = BigInt::from(150i64);
+    ///
+    /// assert_eq!(&a - &b, BigInt::from(-50i64));
+    /// ```
+    fn sub(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        self + &-rhs
+    }
+}
+
+impl<'a, T> ops::Mul<T> for &BigInt
+where
+    T: Into<&'a BigInt>,
+{
+    type Output = BigInt;
+
+    /// Mul operator between two numbers.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigInt::from(-150i64);
+    /// let b = BigInt::from(100i64);
+    ///
+    /// assert_eq!(&a * &b, BigInt::from(-15000i64));
+    /// ```
+    fn mul(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        BigInt::from_parts(self.neg != rhs.neg, &self.mag * &rhs.mag)
+    }
+}
+
+impl<'a, T> ops::Div<T> for &BigInt
+where
+    T: Into<&'a BigInt>,
+{
+    type Output = BigInt;
+
+    /// Div operator between two numbers, rounding toward zero.
+    /// If rhs is `0` it should panic as `division by zero`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigInt::from(-150i64);
+    /// let b = BigInt::from(11i64);
+    ///
+    /// assert_eq!(&a / &b, BigInt::from(-13i64));
+    /// ```
+    fn div(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        self.div_rem(rhs).0
+    }
+}
+
+impl<'a, T> ops::Rem<T> for &BigInt
+where
+    T: Into<&'a BigInt>,
+{
+    type Output = BigInt;
+
+    /// Rem operator between two numbers; the result has the sign of lhs.
+    /// If rhs is `0` it should panic as `division by zero`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigInt::from(-150i64);
+    /// let b = BigInt::from(11i64);
+    ///
+    /// assert_eq!(&a % &b, BigInt::from(-7i64));
+    /// ```
+    fn rem(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        self.div_rem(rhs).1
+    }
+}
+
+impl ops::Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        BigInt::from_parts(!self.neg, self.mag.clone())
+    }
+}
+
+impl ops::Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        BigInt::from_parts(!self.neg, self.mag)
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    /// Comparison operator between two numbers.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let a = BigInt::from(-150i64);
+    /// let b = BigInt::from(100i64);
+    ///
+    /// assert!(a < b);
+    /// ```
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.neg, other.neg) {
+            (false, false) => self.mag.cmp(&other.mag),
+            (true, true) => other.mag.cmp(&self.mag),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(!self.neg, "", &self.mag.to_string())
+    }
+}
+
+impl From<BigUInt> for BigInt {
+    fn from(x: BigUInt) -> Self {
+        BigInt::from_parts(false, x)
+    }
+}
+
+/// Error for converting `BigInt` into a type that cannot hold its value.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TryFromBigIntError;
+
+impl Display for TryFromBigIntError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "out of range integral type conversion attempted")
+    }
+}
+
+impl TryFrom<BigInt> for BigUInt {
+    type Error = TryFromBigIntError;
+
+    fn try_from(x: BigInt) -> Result<Self, Self::Error> {
+        if x.neg {
+            Err(TryFromBigIntError)
+        } else {
+            Ok(x.mag)
+        }
+    }
+}
+
+macro_rules! big_int_from_impl {
+    ($i:ty) => {
+        impl From<$i> for BigInt {
+            fn from(x: $i) -> Self {
+                BigInt::from_parts(x < 0, BigUInt::from(x.unsigned_abs()))
+            }
+        }
+
+        impl TryFrom<&BigInt> for $i {
+            type Error = TryFromBigIntError;
+
+            fn try_from(x: &BigInt) -> Result<Self, Self::Error> {
+                let mag = match x.mag.limbs() {
+                    [] => 0,
+                    [lo] => *lo as u128,
+                    [lo, hi] => (*hi as u128) << 64 | *lo as u128,
+                    _ => return Err(TryFromBigIntError),
+                };
+                let v = if x.neg {
+                    0i128.checked_sub_unsigned(mag)
+                } else {
+                    i128::try_from(mag).ok()
+                };
+                v.and_then(|v| <$i>::try_from(v).ok())
+                    .ok_or(TryFromBigIntError)
+            }
+        }
+    };
+}
+
+big_int_from_impl!(i8);
+big_int_from_impl!(i16);
+big_int_from_impl!(i32);
+big_int_from_impl!(i64);
+big_int_from_impl!(i128);
+big_int_from_impl!(isize);
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::BigInt;
+    use crate::big_uint::BigUInt;
+
+    #[test]
+    fn string_test() {
+        assert_eq!(BigInt::zero().to_string(), "0");
+        assert_eq!(BigInt::from_str("-0").unwrap().to_string(), "0");
+        assert_eq!(BigInt::from_str("+12").unwrap().to_string(), "12");
+
+        let a = BigInt::from_str("-1,000,000,000,000,000,000,000,000").unwrap();
+        assert_eq!(a.to_string(), "-1000000000000000000000000");
+
+        assert!(BigInt::from_str("-").is_err());
+        assert!(BigInt::from_str("--1").is_err());
+    }
+
+    #[test]
+    fn add_sub_test() {
+        fn case(a: &str, b: &str, sum: &str, diff: &str) {
+            let a = BigInt::from_str(a).unwrap();
+            let b = BigInt::from_str(b).unwrap();
+            assert_eq!(&a + &b, BigInt::from_str(sum).unwrap());
+            assert_eq!(&a - &b, BigInt::from_str(diff).unwrap());
+        }
+
+        case("1", "2", "3", "-1");
+        case("-1", "2", "1", "-3");
+        case("-1", "-2", "-3", "1");
+        case(
+            "1,000,000,000,000,000,000,000,000",
+            "-1,000,000,000,000,000,000,000,000",
+            "0",
+            "2,000,000,000,000,000,000,000,000",
+        );
+    }
+
+    #[test]
+    fn mul_test() {
+        fn case(a: &str, b: &str, c: &str) {
+            let a = BigInt::from_str(a).unwrap();
+            let b = BigInt::from_str(b).unwrap();
+            let c = BigInt::from_str(c).unwrap();
+            assert_eq!(&a * &b, c);
+        }
+
+        case(
+            "-9876543210123456789",
+            "1234567890987654321",
+            "-12193263121170553265523548251112635269",
+        );
+        case("-9", "-9", "81");
+        case("-1", "0", "0");
+    }
+
+    #[test]
+    fn div_rem_test() {
+        fn case(a: i64, b: i64) {
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            assert_eq!(&x / &y, BigInt::from(a / b), "{a} / {b}");
+            assert_eq!(&x % &y, BigInt::from(a % b), "{a} % {b}");
+            assert_eq!(x.div_euclid(&y), BigInt::from(a.div_euclid(b)));
+            assert_eq!(x.rem_euclid(&y), BigInt::from(a.rem_euclid(b)));
+            assert_eq!(x.checked_div(&y), Some(BigInt::from(a / b)));
+            assert_eq!(x.checked_rem(&y), Some(BigInt::from(a % b)));
+        }
+
+        for a in [-150, -7, -1, 0, 1, 7, 150] {
+            for b in [-11, -2, -1, 1, 2, 11] {
+                case(a, b);
+            }
+            assert_eq!(BigInt::from(a).checked_div_rem(&BigInt::zero()), None);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn div_by_zero_test() {
+        let _ = &BigInt::one() / &BigInt::zero();
+    }
+
+    #[test]
+    fn cmp_test() {
+        let v = [
+            "-1,000,000,000,000,000,000,000,000",
+            "-5",
+            "0",
+            "3",
+            "1,000,000,000,000,000,000,000,000",
+        ]
+        .map(|x| BigInt::from_str(x).unwrap());
+
+        for (i, a) in v.iter().enumerate() {
+            for (j, b) in v.iter().enumerate() {
+                assert_eq!(a.cmp(b), i.cmp(&j));
+            }
+        }
+    }
+
+    #[test]
+    fn convert_test() {
+        for x in [i64::MIN, -1, 0, 1, i64::MAX] {
+            assert_eq!(i64::try_from(&BigInt::from(x)), Ok(x));
+        }
+
+        assert!(i8::try_from(&BigInt::from(128i64)).is_err());
+        assert_eq!(i8::try_from(&BigInt::from(-128i64)), Ok(-128));
+        assert!(i64::try_from(&BigInt::from_str("-9223372036854775809").unwrap()).is_err());
+
+        for x in [i128::MIN, i128::MIN + 1, -1, 0, i128::MAX] {
+            assert_eq!(BigInt::from(x).to_string(), x.to_string());
+            assert_eq!(i128::try_from(&BigInt::from(x)), Ok(x));
+        }
+        let one = BigInt::from(1i64);
+        assert!(i128::try_from(&(&BigInt::from(i128::MAX) + &one)).is_err());
+        assert!(i128::try_from(&(&BigInt::from(i128::MIN) - &one)).is_err());
+        assert!(i64::try_from(&BigInt::from(i128::MIN)).is_err());
+
+        let a = BigUInt::from(5u64);
+        assert_eq!(BigUInt::try_from(BigInt::from(a.clone())), Ok(a));
+        assert!(BigUInt::try_from(BigInt::from(-5i64)).is_err());
+    }
+}
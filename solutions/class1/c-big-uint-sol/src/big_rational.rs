@@ -0,0 +1,449 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops;
+use std::str::FromStr;
+
+use crate::big_int::BigInt;
+use crate::big_uint::{div_rem, BigUInt, ParseBigUIntError, ParseOptions};
+
+/// Big rational number module
+///
+/// ## Example
+///
+/// ```
+/// let a = BigRational::from_str("3/4").unwrap();
+/// let b = BigRational::from_str("-1.25").unwrap();
+/// assert_eq!((&a + &b).to_string(), "-1/2");
+/// ```
+///
+/// ## Implementation
+///
+/// The number is a signed `BigInt` numerator over a positive `BigUInt` denominator.
+/// Both are always divided by their gcd, so equal values have equal fields.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BigRational {
+    numer: BigInt,
+    denom: BigUInt,
+}
+
+impl BigRational {
+    pub fn zero() -> BigRational {
+        BigRational {
+            numer: BigInt::zero(),
+            denom: BigUInt::one(),
+        }
+    }
+
+    pub fn one() -> BigRational {
+        BigRational {
+            numer: BigInt::one(),
+            denom: BigUInt::one(),
+        }
+    }
+
+    /// `numer / denom` in lowest terms.
+    /// If `denom` is `0` it should panic as `division by zero`.
+    pub fn new(numer: BigInt, denom: BigUInt) -> BigRational {
+        if denom.is_zero() {
+            panic!("division by zero");
+        }
+
+        let g = numer.magnitude().gcd(&denom);
+        let numer = BigInt::from_parts(numer.is_negative(), numer.magnitude() / &g);
+        let denom = &denom / &g;
+
+        BigRational { numer, denom }
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        &self.numer
+    }
+
+    pub fn denom(&self) -> &BigUInt {
+        &self.denom
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.numer.is_negative()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == BigUInt::one()
+    }
+
+    /// `1 / self`.
+    /// If the number is `0` it should panic as `division by zero`.
+    pub fn recip(&self) -> BigRational {
+        if self.numer.is_zero() {
+            panic!("division by zero");
+        }
+
+        BigRational {
+            numer: BigInt::from_parts(self.numer.is_negative(), self.denom.clone()),
+            denom: self.numer.magnitude().clone(),
+        }
+    }
+
+    /// Decimal string with exactly `digits` digits after the point,
+    /// rounding half away from zero.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigRational::from_str("-2/3").unwrap();
+    /// assert_eq!(a.to_decimal_string(4), "-0.6667");
+    /// ```
+    pub fn to_decimal_string(&self, digits: usize) -> String {
+        let scale = BigUInt::from(10u64).pow(digits as u32);
+        let (mut q, r) = div_rem(&(self.numer.magnitude() * &scale), &self.denom);
+
+        if &r + &r >= self.denom {
+            q = &q + &BigUInt::one();
+        }
+
+        let s = q.to_string();
+        let s = format!("{s:0>width$}", width = digits + 1);
+        let (int, frac) = s.split_at(s.len() - digits);
+        let sign = if self.numer.is_negative() && !q.is_zero() {
+            "-"
+        } else {
+            ""
+        };
+
+        if digits == 0 {
+            format!("{sign}{int}")
+        } else {
+            format!("{sign}{int}.{frac}")
+        }
+    }
+}
+
+/// Error for parsing `BigRational`.
+#[derive(Debug, Copy, Clone)]
+pub enum ParseBigRationalError {
+    Number(ParseBigUIntError),
+    ZeroDenominator,
+}
+
+impl Display for ParseBigRationalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseBigRationalError::Number(e) => write!(f, "{e}"),
+            ParseBigRationalError::ZeroDenominator => write!(f, "denominator is zero"),
+        }
+    }
+}
+
+impl From<ParseBigUIntError> for ParseBigRationalError {
+    fn from(e: ParseBigUIntError) -> Self {
+        ParseBigRationalError::Number(e)
+    }
+}
+
+impl FromStr for BigRational {
+    type Err = ParseBigRationalError;
+
+    /// String to rational number. It can be a fraction like `-3/4`,
+    /// a decimal like `-1.25`, or an integer, all in decimal digits.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigRational::from_str("6/8").unwrap();
+    /// assert_eq!(a.to_string(), "3/4");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let decimal = ParseOptions::new().radix_prefix(false);
+
+        if let Some((n, d)) = s.split_once('/') {
+            let numer = BigInt::parse_with(n, &decimal)?;
+            let denom = decimal.parse(d).map_err(|e| e.shifted(n.len() + 1))?;
+
+            if denom.is_zero() {
+                Err(ParseBigRationalError::ZeroDenominator)
+            } else {
+                Ok(BigRational::new(numer, denom))
+            }
+        } else if let Some((int, frac)) = s.split_once('.') {
+            let point = int.len();
+            let int = BigInt::parse_with(int, &decimal)?;
+            let digits = frac.chars().filter(|c| c.is_ascii_digit()).count();
+            let scale = BigUInt::from(10u64).pow(digits as u32);
+            let frac = BigUInt::from_str_radix(frac, 10).map_err(|e| e.shifted(point + 1))?;
+
+            let mag = &(int.magnitude() * &scale) + &frac;
+            let neg = s.starts_with('-');
+            Ok(BigRational::new(BigInt::from_parts(neg, mag), scale))
+        } else {
+            Ok(BigRational::from(BigInt::parse_with(s, &decimal)?))
+        }
+    }
+}
+
+impl<'a, T> ops::Add<T> for &BigRational
+where
+    T: Into<&'a BigRational>,
+{
+    type Output = BigRational;
+
+    /// Add operator between two numbers.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigRational::from_str("1/2").unwrap();
+    /// let b = BigRational::from_str("1/3").unwrap();
+    ///
+    /// assert_eq!(&a + &b, BigRational::from_str("5/6").unwrap());
+    /// ```
+    fn add(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        let lhs_numer = &self.numer * &BigInt::from(rhs.denom.clone());
+        let rhs_numer = &rhs.numer * &BigInt::from(self.denom.clone());
+
+        BigRational::new(&lhs_numer + &rhs_numer, &self.denom * &rhs.denom)
+    }
+}
+
+impl<'a, T> ops::Sub<T> for &BigRational
+where
+    T: Into<&'a BigRational>,
+{
+    type Output = BigRational;
+
+    /// Sub operator between two numbers.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigRational::from_str("1/3").unwrap();
+    /// let b = BigRational::from_str("1/2").unwrap();
+    ///
+    /// assert_eq!(&a - &b, BigRational::from_str("-1/6").unwrap());
+    /// ```
+    fn sub(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        self + &-rhs
+    }
+}
+
+impl<'a, T> ops::Mul<T> for &BigRational
+where
+    T: Into<&'a BigRational>,
+{
+    type Output = BigRational;
+
+    /// Mul operator between two numbers.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigRational::from_str("2/3").unwrap();
+    /// let b = BigRational::from_str("-9/4").unwrap();
+    ///
+    /// assert_eq!(&a * &b, BigRational::from_str("-3/2").unwrap());
+    /// ```
+    fn mul(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        BigRational::new(&self.numer * &rhs.numer, &self.denom * &rhs.denom)
+    }
+}
+
+impl<'a, T> ops::Div<T> for &BigRational
+where
+    T: Into<&'a BigRational>,
+{
+    type Output = BigRational;
+
+    /// Div operator between two numbers.
+    /// If rhs is `0` it should panic as `division by zero`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigRational::from_str("2/3").unwrap();
+    /// let b = BigRational::from_str("4/9").unwrap();
+    ///
+    /// assert_eq!(&a / &b, BigRational::from_str("3/2").unwrap());
+    /// ```
+    fn div(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        if rhs.is_zero() {
+            panic!("division by zero");
+        }
+
+        let numer = BigInt::from_parts(rhs.numer.is_negative(), rhs.denom.clone());
+        BigRational::new(&self.numer * &numer, &self.denom * rhs.numer.magnitude())
+    }
+}
+
+impl ops::Neg for &BigRational {
+    type Output = BigRational;
+
+    fn neg(self) -> Self::Output {
+        BigRational {
+            numer: -&self.numer,
+            denom: self.denom.clone(),
+        }
+    }
+}
+
+impl ops::Neg for BigRational {
+    type Output = BigRational;
+
+    fn neg(self) -> Self::Output {
+        BigRational {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl PartialOrd for BigRational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigRational {
+    /// Comparison operator between two numbers.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let a = BigRational::from_str("2/3").unwrap();
+    /// let b = BigRational::from_str("3/4").unwrap();
+    ///
+    /// assert!(a < b);
+    /// ```
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = &self.numer * &BigInt::from(other.denom.clone());
+        let rhs = &other.numer * &BigInt::from(self.denom.clone());
+        lhs.cmp(&rhs)
+    }
+}
+
+impl Display for BigRational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl From<BigInt> for BigRational {
+    fn from(x: BigInt) -> Self {
+        BigRational {
+            numer: x,
+            denom: BigUInt::one(),
+        }
+    }
+}
+
+impl From<BigUInt> for BigRational {
+    fn from(x: BigUInt) -> Self {
+        BigRational::from(BigInt::from(x))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::BigRational;
+
+    #[test]
+    fn string_test() {
+        fn case(s: &str, c: &str) {
+            assert_eq!(BigRational::from_str(s).unwrap().to_string(), c, "{s}");
+        }
+
+        case("3/4", "3/4");
+        case("6/8", "3/4");
+        case("-6/8", "-3/4");
+        case("0/5", "0");
+        case("10/5", "2");
+        case("-7", "-7");
+        case("-1.25", "-5/4");
+        case("0.5", "1/2");
+        case("-0.05", "-1/20");
+        case("12.000", "12");
+        case("1.2_5", "5/4");
+        case("0.000_001", "1/1000000");
+        case(
+            "1,000,000,000,000,000,000,000/3",
+            "1000000000000000000000/3",
+        );
+
+        assert!(BigRational::from_str("1/0").is_err());
+        assert!(BigRational::from_str("1/-2").is_err());
+        assert!(BigRational::from_str("1.").is_err());
+        assert!(BigRational::from_str(".5").is_err());
+        assert!(BigRational::from_str("a/2").is_err());
+        assert!(BigRational::from_str("0x10.5").is_err());
+        assert!(BigRational::from_str("1/0x10").is_err());
+        assert!(BigRational::from_str("0b11/2").is_err());
+        assert!(BigRational::from_str("-0o7").is_err());
+    }
+
+    #[test]
+    fn arith_test() {
+        fn case(a: &str, b: &str, sum: &str, diff: &str, prod: &str, quot: &str) {
+            let a = BigRational::from_str(a).unwrap();
+            let b = BigRational::from_str(b).unwrap();
+            let r = |s| BigRational::from_str(s).unwrap();
+
+            assert_eq!(&a + &b, r(sum));
+            assert_eq!(&a - &b, r(diff));
+            assert_eq!(&a * &b, r(prod));
+            assert_eq!(&a / &b, r(quot));
+        }
+
+        case("1/2", "1/3", "5/6", "1/6", "1/6", "3/2");
+        case("-3/4", "1/4", "-1/2", "-1", "-3/16", "-3");
+        case("2", "-0.5", "3/2", "5/2", "-1", "-4");
+        case("0", "7/9", "7/9", "-7/9", "0", "0");
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn div_by_zero_test() {
+        let _ = &BigRational::one() / &BigRational::zero();
+    }
+
+    #[test]
+    fn cmp_test() {
+        let v = ["-5/2", "-2", "-1/3", "0", "1/3", "0.34", "2/3", "7"]
+            .map(|x| BigRational::from_str(x).unwrap());
+
+        for (i, a) in v.iter().enumerate() {
+            for (j, b) in v.iter().enumerate() {
+                assert_eq!(a.cmp(b), i.cmp(&j));
+            }
+        }
+    }
+
+    #[test]
+    fn to_decimal_string_test() {
+        fn case(s: &str, digits: usize, c: &str) {
+            let a = BigRational::from_str(s).unwrap();
+            assert_eq!(a.to_decimal_string(digits), c, "{s} {digits}");
+        }
+
+        case("1/3", 5, "0.33333");
+        case("2/3", 5, "0.66667");
+        case("-2/3", 4, "-0.6667");
+        case("1/8", 2, "0.13");
+        case("-1/8", 2, "-0.13");
+        case("-1/1000", 2, "0.00");
+        case("22/7", 0, "3");
+        case("123456789/100", 1, "1234567.9");
+        case("1/7", 30, "0.142857142857142857142857142857");
+    }
+}
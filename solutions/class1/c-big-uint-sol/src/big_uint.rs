@@ -2,7 +2,58 @@ use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 use std::ops::ControlFlow;
 use std::str::FromStr;
-use std::{iter, ops};
+use std::sync::atomic::{self, AtomicUsize};
+use std::{iter, ops, thread};
+
+mod arith;
+mod bits;
+mod bytes;
+mod convert;
+mod div;
+mod format;
+pub(crate) mod kernel;
+mod limbs;
+mod modular;
+mod montgomery;
+mod mul;
+mod ntt;
+mod par;
+mod parse;
+mod prime;
+mod radix;
+mod rng;
+mod root;
+
+pub use format::FormatSpec;
+pub use montgomery::MontgomeryContext;
+pub use parse::ParseOptions;
+pub use rng::{BigRng, XorShift64};
+
+use limbs::Limbs;
+
+/// Thread count set by `set_threads`, or `0` for the default.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets how many threads the `par_*` methods may use. `0` restores the
+/// default, which is the number of CPUs available to the process.
+///
+/// ## Examples
+///
+/// ```
+/// big_uint::set_threads(4);
+/// assert_eq!(big_uint::threads(), 4);
+/// ```
+pub fn set_threads(n: usize) {
+    THREADS.store(n, atomic::Ordering::Relaxed);
+}
+
+/// Number of threads the `par_*` methods may use.
+pub fn threads() -> usize {
+    match THREADS.load(atomic::Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
 
 /// Big unsigned integer module
 ///
@@ -22,71 +73,135 @@ use std::{iter, ops};
 /// The big number is implemented by vector of `u64`.
 /// Each element can have the value of range `0` to `u64::MAX`.
 /// The value of the number is `u64::MAX + 1` when the vector is `[0, 1]`.
+/// Numbers of up to two elements are stored inline, without a heap allocation.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct BigUInt {
-    inner: Vec<u64>,
+    inner: Limbs,
 }
 
 impl BigUInt {
     pub const fn zero() -> BigUInt {
-        BigUInt { inner: Vec::new() }
+        BigUInt {
+            inner: Limbs::new(),
+        }
     }
 
     pub fn one() -> BigUInt {
-        BigUInt { inner: vec![1] }
+        BigUInt::from(1u64)
     }
 
-    /// Shift left once; same as *2 of the value
-    fn shl_once(&mut self) {
-        let mut carry = false;
+    pub fn is_zero(&self) -> bool {
+        self.inner.is_empty()
+    }
 
-        for i in self.inner.iter_mut() {
-            *i = i.rotate_left(1);
-            let c = *i & 1;
-            *i = (*i ^ c) | carry as u64;
-            carry = c != 0;
-        }
+    /// Little-endian limbs of the number, without high zero limbs.
+    pub(crate) fn limbs(&self) -> &[u64] {
+        &self.inner
+    }
 
-        if carry {
-            self.inner.push(1);
+    /// Number from little-endian limbs; high zero limbs are removed.
+    pub(crate) fn from_limbs(inner: impl Into<Limbs>) -> BigUInt {
+        let mut inner = inner.into();
+        inner.trim();
+        BigUInt { inner }
+    }
+}
+
+/// Error for parsing `BigUInt`: what went wrong and where.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ParseBigUIntError {
+    kind: ParseBigUIntErrorKind,
+    offset: usize,
+    found: Option<char>,
+}
+
+/// What made a string fail to parse as `BigUInt`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ParseBigUIntErrorKind {
+    /// There are no digits.
+    Empty,
+    NotStartingWithDigit,
+    LeadingZero,
+    InvalidCharacter,
+    /// Separators do not split the digits into groups of three.
+    BadGrouping,
+}
+
+impl ParseBigUIntError {
+    pub(crate) fn new(kind: ParseBigUIntErrorKind, offset: usize, found: Option<char>) -> Self {
+        ParseBigUIntError {
+            kind,
+            offset,
+            found,
         }
     }
 
-    /// Shift right once; same as /2 of the value
-    fn shr_once(&mut self) {
-        let mut carry = false;
+    pub fn kind(&self) -> ParseBigUIntErrorKind {
+        self.kind
+    }
 
-        for i in self.inner.iter_mut().rev() {
-            let c = *i & 1 != 0;
-            *i >>= 1;
-            *i |= (carry as u64) << 63;
-            carry = c;
+    /// Byte offset in the input where the error was found.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Character at `offset`, or `None` if the input ended there.
+    pub fn found(&self) -> Option<char> {
+        self.found
+    }
+
+    /// The same error in an input with `n` more bytes in front.
+    pub(crate) fn shifted(self, n: usize) -> Self {
+        ParseBigUIntError {
+            offset: self.offset + n,
+            ..self
         }
+    }
+}
 
-        if let Some(&0) = self.inner.last() {
-            self.inner.pop();
+impl Display for ParseBigUIntError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let at = self.offset;
+        let c = self.found.unwrap_or_default();
+
+        match self.kind {
+            ParseBigUIntErrorKind::Empty if at == 0 => write!(f, "empty string"),
+            ParseBigUIntErrorKind::Empty => write!(f, "missing digits at byte {at}"),
+            ParseBigUIntErrorKind::NotStartingWithDigit => {
+                write!(
+                    f,
+                    "number does not start with digit, found `{c}` at byte {at}"
+                )
+            }
+            ParseBigUIntErrorKind::LeadingZero => {
+                write!(f, "number contains leading zero at byte {at}")
+            }
+            ParseBigUIntErrorKind::InvalidCharacter => {
+                write!(f, "number contains invalid character `{c}` at byte {at}")
+            }
+            ParseBigUIntErrorKind::BadGrouping => {
+                write!(f, "digits are not grouped in threes at byte {at}")
+            }
         }
     }
 }
 
-/// Error for parsing `BigUInt`.
-#[derive(Debug, Copy, Clone)]
-pub enum ParseBigUIntError {
-    Empty,
-    NotStartingWithDigit,
-    LeadingZero,
-    InvalidCharacter,
+/// Error for converting between `BigUInt` and a primitive type.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TryFromBigUIntError {
+    /// The value does not fit the target type.
+    OutOfRange,
+    /// The float is NaN or has a fractional part.
+    NotInteger,
 }
 
-impl Display for ParseBigUIntError {
+impl Display for TryFromBigUIntError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            ParseBigUIntError::Empty => write!(f, "empty string"),
-            ParseBigUIntError::NotStartingWithDigit => {
-                write!(f, "number does not start with digit")
+            TryFromBigUIntError::OutOfRange => {
+                write!(f, "out of range integral type conversion attempted")
             }
-            ParseBigUIntError::LeadingZero => write!(f, "number contains leading zero"),
-            ParseBigUIntError::InvalidCharacter => write!(f, "number contains invalid character"),
+            TryFromBigUIntError::NotInteger => write!(f, "float is not an integer"),
         }
     }
 }
@@ -95,36 +210,18 @@ impl FromStr for BigUInt {
     type Err = ParseBigUIntError;
 
     /// String to big integer. Input can contain `,` and `_` and will be ignored.
+    /// A `0x`, `0o` or `0b` prefix reads the rest as hex, octal or binary.
     /// If the string is invalid, it will return an error with description.
+    /// `ParseOptions` reads the same numbers with other rules.
     ///
     /// ## Examples
     ///
     /// ```
     /// let c = BigUInt::from_str("1,000,000,000,000,000,000,000,000");
+    /// let d = BigUInt::from_str("0xdead_beef");
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some(first) = s.chars().next() {
-            if first == '0' {
-                if s.len() == 1 {
-                    Ok(BigUInt::zero())
-                } else {
-                    Err(ParseBigUIntError::LeadingZero)
-                }
-            } else if !first.is_ascii_digit() {
-                Err(ParseBigUIntError::NotStartingWithDigit)
-            } else {
-                s.chars().try_fold(BigUInt::zero(), |a, i| match i {
-                    '_' | ',' => Ok(a),
-                    '0'..='9' => {
-                        let a = &a * &BigUInt::from(10u64);
-                        Ok(&a + &BigUInt::from(i as u8 - b'0'))
-                    }
-                    _ => Err(ParseBigUIntError::InvalidCharacter),
-                })
-            }
-        } else {
-            Err(ParseBigUIntError::Empty)
-        }
+        ParseOptions::new().parse(s)
     }
 }
 
@@ -210,9 +307,7 @@ where
                 }
             }
 
-            while let Some(&0) = inner.last() {
-                inner.pop();
-            }
+            inner.trim();
 
             BigUInt { inner }
         }
@@ -226,6 +321,7 @@ where
     type Output = BigUInt;
 
     /// Mul operator between two numbers.
+    /// Large operands are multiplied with Karatsuba or Toom-3.
     ///
     /// ## Examples
     ///
@@ -237,63 +333,101 @@ where
     /// ```
     fn mul(self, rhs: T) -> Self::Output {
         let rhs = rhs.into();
-        let mut ans = BigUInt::zero();
 
-        for (i, x) in rhs.inner.iter().enumerate() {
-            let mut inner = self.inner.clone();
-            let mut carry = 0u64;
-
-            for j in inner.iter_mut() {
-                let mul = *j as u128 * *x as u128 + carry as u128;
-                *j = mul as u64;
-                carry = (mul >> 64) as u64;
-            }
-
-            if carry > 0 {
-                inner.push(carry);
-            }
-
-            ans = &ans
-                + &BigUInt {
-                    inner: std::iter::repeat(0)
-                        .take(i)
-                        .chain(inner.into_iter())
-                        .collect(),
-                };
+        if let ([a], [b]) = (&self.inner[..], &rhs.inner[..]) {
+            return BigUInt::from(*a as u128 * *b as u128);
         }
 
-        ans
+        BigUInt::from_limbs(mul::mul(&self.inner, &rhs.inner))
     }
 }
 
 /// This function returns division and remainder of lhs and rhs.
-fn div_rem(lhs: &BigUInt, rhs: &BigUInt) -> (BigUInt, BigUInt) {
+pub(crate) fn div_rem(lhs: &BigUInt, rhs: &BigUInt) -> (BigUInt, BigUInt) {
     if rhs.inner.is_empty() {
         panic!("division by zero");
-    } else if lhs < rhs {
-        (BigUInt::zero(), lhs.clone())
-    } else {
-        let mut lhs = lhs.clone();
-        let mut rhs = rhs.clone();
-        let mut shift = BigUInt::one();
-        let mut ans = BigUInt::zero();
-
-        while lhs >= rhs {
-            rhs.shl_once();
-            shift.shl_once();
+    }
+
+    if let (Ok(a), Ok(b)) = (u128::try_from(lhs), u128::try_from(rhs)) {
+        return (BigUInt::from(a / b), BigUInt::from(a % b));
+    }
+
+    let (q, r) = div::div_rem(&lhs.inner, &rhs.inner);
+    (BigUInt::from_limbs(q), BigUInt::from_limbs(r))
+}
+
+impl BigUInt {
+    /// `self - rhs`, or `None` if `rhs` is bigger.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(100u64);
+    /// let b = BigUInt::from(150u64);
+    ///
+    /// assert_eq!(b.checked_sub(&a), Some(BigUInt::from(50u64)));
+    /// assert_eq!(a.checked_sub(&b), None);
+    /// ```
+    pub fn checked_sub(&self, rhs: &BigUInt) -> Option<BigUInt> {
+        if self < rhs {
+            None
+        } else {
+            Some(self - rhs)
         }
+    }
 
-        while !lhs.inner.is_empty() && !shift.inner.is_empty() {
-            if lhs >= rhs {
-                lhs = &lhs - &rhs;
-                ans = &ans + &shift;
-            }
+    /// `self - rhs`, or `0` if `rhs` is bigger. Same as the `-` operator.
+    pub fn saturating_sub(&self, rhs: &BigUInt) -> BigUInt {
+        self - rhs
+    }
 
-            rhs.shr_once();
-            shift.shr_once();
+    /// `|self - rhs|`
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(100u64);
+    /// let b = BigUInt::from(150u64);
+    ///
+    /// assert_eq!(a.abs_diff(&b), BigUInt::from(50u64));
+    /// ```
+    pub fn abs_diff(&self, rhs: &BigUInt) -> BigUInt {
+        if self < rhs {
+            rhs - self
+        } else {
+            self - rhs
         }
+    }
 
-        (ans, lhs)
+    /// Quotient and remainder, or `None` if `rhs` is `0`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(150u64);
+    ///
+    /// assert_eq!(
+    ///     a.checked_div_rem(&BigUInt::from(11u64)),
+    ///     Some((BigUInt::from(13u64), BigUInt::from(7u64)))
+    /// );
+    /// assert_eq!(a.checked_div_rem(&BigUInt::zero()), None);
+    /// ```
+    pub fn checked_div_rem(&self, rhs: &BigUInt) -> Option<(BigUInt, BigUInt)> {
+        if rhs.is_zero() {
+            None
+        } else {
+            Some(div_rem(self, rhs))
+        }
+    }
+
+    /// `self / rhs`, or `None` if `rhs` is `0`.
+    pub fn checked_div(&self, rhs: &BigUInt) -> Option<BigUInt> {
+        self.checked_div_rem(rhs).map(|(q, _)| q)
+    }
+
+    /// `self % rhs`, or `None` if `rhs` is `0`.
+    pub fn checked_rem(&self, rhs: &BigUInt) -> Option<BigUInt> {
+        self.checked_div_rem(rhs).map(|(_, r)| r)
     }
 }
 
@@ -344,6 +478,12 @@ where
 }
 
 impl PartialOrd for BigUInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUInt {
     /// Comparison operator between two numbers.
     ///
     /// ## Example
@@ -354,49 +494,27 @@ impl PartialOrd for BigUInt {
     ///
     /// assert!(a > b);
     /// ```
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    fn cmp(&self, other: &Self) -> Ordering {
         match self.inner.len().cmp(&other.inner.len()) {
             Ordering::Equal => match iter::zip(self.inner.iter().rev(), other.inner.iter().rev())
                 .try_for_each(|(x, y)| match x.cmp(y) {
                     Ordering::Equal => ControlFlow::Continue(()),
                     x => ControlFlow::Break(x),
                 }) {
-                ControlFlow::Continue(_) => Some(Ordering::Equal),
-                ControlFlow::Break(x) => Some(x),
+                ControlFlow::Continue(_) => Ordering::Equal,
+                ControlFlow::Break(x) => x,
             },
-            x => Some(x),
+            x => x,
         }
     }
 }
 
 impl Display for BigUInt {
+    /// Decimal digits of the number, padded to the width if one is given.
+    /// Large numbers are split by powers of `10^19` instead of being divided
+    /// down one limb at a time.
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut t = self.clone();
-        let mut v = Vec::new();
-
-        if self.inner.is_empty() {
-            write!(f, "0")
-        } else {
-            let str_mod = BigUInt::from(1_000_000_000_000_000_000u64);
-
-            while !t.inner.is_empty() {
-                let a = &t % &str_mod;
-                v.push(if let Some(x) = a.inner.first() { *x } else { 0 });
-                t = &t / &str_mod;
-            }
-
-            v.into_iter()
-                .rev()
-                .try_fold(true, |first, t| {
-                    if first {
-                        write!(f, "{t}")?;
-                    } else {
-                        write!(f, "{t:018}")?;
-                    }
-                    Ok(false)
-                })
-                .map(|_| ())
-        }
+        f.pad_integral(true, "", &self.to_str_radix(10))
     }
 }
 
@@ -404,9 +522,7 @@ macro_rules! big_uint_from_impl {
     ($i:ty) => {
         impl From<$i> for BigUInt {
             fn from(x: $i) -> Self {
-                BigUInt {
-                    inner: vec![x as u64],
-                }
+                BigUInt::from_limbs(&[x as u64][..])
             }
         }
     };
@@ -422,7 +538,7 @@ big_uint_from_impl!(usize);
 mod test {
     use std::str::FromStr;
 
-    use super::BigUInt;
+    use super::{BigUInt, XorShift64};
 
     #[test]
     fn string_test() {
@@ -571,6 +687,46 @@ mod test {
         case("1", "0", "0");
     }
 
+    #[test]
+    fn checked_test() {
+        fn case(a: &str, b: &str, diff: Option<&str>, div_rem: Option<(&str, &str)>) {
+            let a = BigUInt::from_str(a).unwrap();
+            let b = BigUInt::from_str(b).unwrap();
+            let diff = diff.map(|x| BigUInt::from_str(x).unwrap());
+            let div_rem = div_rem
+                .map(|(q, r)| (BigUInt::from_str(q).unwrap(), BigUInt::from_str(r).unwrap()));
+
+            assert_eq!(a.checked_sub(&b), diff);
+            assert_eq!(
+                a.saturating_sub(&b),
+                diff.clone().unwrap_or_else(BigUInt::zero)
+            );
+            assert_eq!(a.abs_diff(&b), b.abs_diff(&a));
+            assert_eq!(a.checked_div(&b), div_rem.clone().map(|x| x.0));
+            assert_eq!(a.checked_rem(&b), div_rem.clone().map(|x| x.1));
+            assert_eq!(a.checked_div_rem(&b), div_rem);
+        }
+
+        case("150", "100", Some("50"), Some(("1", "50")));
+        case("100", "150", None, Some(("0", "100")));
+        case("100", "100", Some("0"), Some(("1", "0")));
+        case("0", "0", Some("0"), None);
+        case("1", "0", Some("1"), None);
+        case(
+            "18446744073709551616",
+            "18446744073709551617",
+            None,
+            Some(("0", "18446744073709551616")),
+        );
+
+        let a = BigUInt::from(100u64);
+        let b = BigUInt::from_str("100000000000000000000").unwrap();
+        assert_eq!(
+            a.abs_diff(&b),
+            BigUInt::from_str("99999999999999999900").unwrap()
+        );
+    }
+
     #[test]
     fn cmp_test() {
         fn case(a: &str, b: &str) {
@@ -592,4 +748,22 @@ mod test {
             "1,000,000,000,000,000,000,000,000",
         );
     }
+
+    #[test]
+    fn random_arith_test() {
+        let mut rng = XorShift64::new(2024);
+
+        for bits in [1, 64, 65, 300, 3000, 20000] {
+            for _ in 0..4 {
+                let a = BigUInt::random_bits(bits, &mut rng);
+                let b = &BigUInt::random_bits(bits / 2 + 1, &mut rng) + &BigUInt::one();
+
+                assert_eq!(&(&a + &b) - &b, a);
+                assert_eq!(&(&a * &b) / &b, a);
+                assert_eq!(&(&a * &b) % &b, BigUInt::zero());
+                assert_eq!(&(&(&a / &b) * &b) + &(&a % &b), a);
+                assert!(&a % &b < b);
+            }
+        }
+    }
 }
@@ -0,0 +1,391 @@
+//! Compound assignment, owned and primitive operator overloads for `BigUInt`.
+//!
+//! Every operator here is a thin wrapper around one of the `*_limbs` methods,
+//! which update `self.inner` in place from a limb slice. Primitive operands
+//! are spread into a two-limb array on the stack, so they never allocate a
+//! temporary `BigUInt`, and single-limb operands use the linear kernels.
+
+use std::ops;
+
+use super::{div, kernel, mul, BigUInt};
+
+impl BigUInt {
+    /// `self += b`
+    fn add_limbs(&mut self, b: &[u64]) {
+        if self.inner.len() < b.len() {
+            self.inner.resize(b.len(), 0);
+        }
+        if kernel::add_assign(&mut self.inner, b) {
+            self.inner.push(1);
+        }
+        self.inner.trim();
+    }
+
+    /// `self -= b`, or `0` if `b` is bigger.
+    fn sub_limbs(&mut self, b: &[u64]) {
+        if kernel::cmp(&self.inner, b).is_le() {
+            self.inner.clear();
+        } else {
+            kernel::sub_assign(&mut self.inner, &b[..kernel::trimmed_len(b)]);
+            self.inner.trim();
+        }
+    }
+
+    /// `self *= b`
+    fn mul_limbs(&mut self, b: &[u64]) {
+        match &b[..kernel::trimmed_len(b)] {
+            [] => self.inner.clear(),
+            &[m] => {
+                let carry = kernel::mul_limb(&mut self.inner, m);
+                if carry != 0 {
+                    self.inner.push(carry);
+                }
+            }
+            b => self.inner = mul::mul(&self.inner, b).into(),
+        }
+    }
+
+    /// `self /= b`
+    fn div_limbs(&mut self, b: &[u64]) {
+        match &b[..kernel::trimmed_len(b)] {
+            [] => panic!("division by zero"),
+            &[m] => {
+                kernel::div_rem_limb(&mut self.inner, m);
+                self.inner.trim();
+            }
+            b => self.inner = div::div_rem(&self.inner, b).0.into(),
+        }
+    }
+
+    /// `self %= b`
+    fn rem_limbs(&mut self, b: &[u64]) {
+        match &b[..kernel::trimmed_len(b)] {
+            [] => panic!("division by zero"),
+            &[m] => {
+                let r = kernel::rem_limb(&self.inner, m);
+                self.inner.clear();
+                if r != 0 {
+                    self.inner.push(r);
+                }
+            }
+            b => self.inner = div::div_rem(&self.inner, b).1.into(),
+        }
+    }
+
+    /// `self &= b`
+    fn and_limbs(&mut self, b: &[u64]) {
+        self.inner.resize(self.inner.len().min(b.len()), 0);
+        self.inner.iter_mut().zip(b).for_each(|(x, y)| *x &= y);
+        self.inner.trim();
+    }
+
+    /// `self |= b`
+    fn or_limbs(&mut self, b: &[u64]) {
+        if self.inner.len() < b.len() {
+            self.inner.resize(b.len(), 0);
+        }
+        self.inner.iter_mut().zip(b).for_each(|(x, y)| *x |= y);
+    }
+
+    /// `self ^= b`
+    fn xor_limbs(&mut self, b: &[u64]) {
+        if self.inner.len() < b.len() {
+            self.inner.resize(b.len(), 0);
+        }
+        self.inner.iter_mut().zip(b).for_each(|(x, y)| *x ^= y);
+        self.inner.trim();
+    }
+}
+
+/// Little-endian limbs of a primitive operand.
+fn prim_limbs(x: u128) -> [u64; 2] {
+    [x as u64, (x >> 64) as u64]
+}
+
+macro_rules! big_uint_op_impl {
+    ($op:ident, $f:ident, $op_assign:ident, $f_assign:ident, $limbs:ident) => {
+        big_uint_op_impl!(@owned $op, $f, $op_assign, $f_assign, $limbs);
+
+        impl ops::$op<BigUInt> for &BigUInt {
+            type Output = BigUInt;
+
+            fn $f(self, rhs: BigUInt) -> Self::Output {
+                let mut lhs = self.clone();
+                lhs.$limbs(&rhs.inner);
+                lhs
+            }
+        }
+    };
+    // The operands can be swapped, so the owned right-hand side is updated
+    // in place instead of copying the borrowed one.
+    ($op:ident, $f:ident, $op_assign:ident, $f_assign:ident, $limbs:ident, commutative) => {
+        big_uint_op_impl!(@owned $op, $f, $op_assign, $f_assign, $limbs);
+
+        impl ops::$op<BigUInt> for &BigUInt {
+            type Output = BigUInt;
+
+            fn $f(self, mut rhs: BigUInt) -> Self::Output {
+                rhs.$limbs(&self.inner);
+                rhs
+            }
+        }
+    };
+    (@owned $op:ident, $f:ident, $op_assign:ident, $f_assign:ident, $limbs:ident) => {
+        impl ops::$op_assign<&BigUInt> for BigUInt {
+            fn $f_assign(&mut self, rhs: &BigUInt) {
+                self.$limbs(&rhs.inner);
+            }
+        }
+
+        impl ops::$op_assign<BigUInt> for BigUInt {
+            fn $f_assign(&mut self, rhs: BigUInt) {
+                self.$limbs(&rhs.inner);
+            }
+        }
+
+        impl ops::$op<&BigUInt> for BigUInt {
+            type Output = BigUInt;
+
+            fn $f(mut self, rhs: &BigUInt) -> Self::Output {
+                self.$limbs(&rhs.inner);
+                self
+            }
+        }
+
+        impl ops::$op<BigUInt> for BigUInt {
+            type Output = BigUInt;
+
+            fn $f(mut self, rhs: BigUInt) -> Self::Output {
+                self.$limbs(&rhs.inner);
+                self
+            }
+        }
+    };
+}
+
+big_uint_op_impl!(Add, add, AddAssign, add_assign, add_limbs, commutative);
+big_uint_op_impl!(Sub, sub, SubAssign, sub_assign, sub_limbs);
+big_uint_op_impl!(Mul, mul, MulAssign, mul_assign, mul_limbs, commutative);
+big_uint_op_impl!(Div, div, DivAssign, div_assign, div_limbs);
+big_uint_op_impl!(Rem, rem, RemAssign, rem_assign, rem_limbs);
+big_uint_op_impl!(
+    BitAnd,
+    bitand,
+    BitAndAssign,
+    bitand_assign,
+    and_limbs,
+    commutative
+);
+big_uint_op_impl!(
+    BitOr,
+    bitor,
+    BitOrAssign,
+    bitor_assign,
+    or_limbs,
+    commutative
+);
+big_uint_op_impl!(
+    BitXor,
+    bitxor,
+    BitXorAssign,
+    bitxor_assign,
+    xor_limbs,
+    commutative
+);
+
+macro_rules! big_uint_prim_op_impl {
+    ($op:ident, $f:ident, $op_assign:ident, $f_assign:ident, $limbs:ident, $($t:ty),*) => {$(
+        impl ops::$op_assign<$t> for BigUInt {
+            fn $f_assign(&mut self, rhs: $t) {
+                self.$limbs(&prim_limbs(rhs as u128));
+            }
+        }
+
+        impl ops::$op<$t> for BigUInt {
+            type Output = BigUInt;
+
+            fn $f(mut self, rhs: $t) -> Self::Output {
+                self.$limbs(&prim_limbs(rhs as u128));
+                self
+            }
+        }
+
+        impl ops::$op<$t> for &BigUInt {
+            type Output = BigUInt;
+
+            fn $f(self, rhs: $t) -> Self::Output {
+                let mut lhs = self.clone();
+                lhs.$limbs(&prim_limbs(rhs as u128));
+                lhs
+            }
+        }
+    )*};
+}
+
+big_uint_prim_op_impl!(Add, add, AddAssign, add_assign, add_limbs, u8, u16, u32, u64, u128, usize);
+big_uint_prim_op_impl!(Sub, sub, SubAssign, sub_assign, sub_limbs, u8, u16, u32, u64, u128, usize);
+big_uint_prim_op_impl!(Mul, mul, MulAssign, mul_assign, mul_limbs, u8, u16, u32, u64, u128, usize);
+big_uint_prim_op_impl!(Div, div, DivAssign, div_assign, div_limbs, u8, u16, u32, u64, u128, usize);
+big_uint_prim_op_impl!(Rem, rem, RemAssign, rem_assign, rem_limbs, u8, u16, u32, u64, u128, usize);
+
+macro_rules! prim_big_uint_op_impl {
+    ($op:ident, $f:ident, $($t:ty),*) => {$(
+        impl ops::$op<BigUInt> for $t {
+            type Output = BigUInt;
+
+            fn $f(self, rhs: BigUInt) -> Self::Output {
+                ops::$op::$f(rhs, self)
+            }
+        }
+
+        impl ops::$op<&BigUInt> for $t {
+            type Output = BigUInt;
+
+            fn $f(self, rhs: &BigUInt) -> Self::Output {
+                ops::$op::$f(rhs, self)
+            }
+        }
+    )*};
+}
+
+// Only the commutative operators are provided with the primitive on the left.
+prim_big_uint_op_impl!(Add, add, u8, u16, u32, u64, u128, usize);
+prim_big_uint_op_impl!(Mul, mul, u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod test {
+    use crate::big_uint::{BigUInt, XorShift64};
+
+    #[test]
+    fn assign_test() {
+        let mut rng = XorShift64::new(17);
+
+        for (x, y) in [
+            (0, 0),
+            (0, 70),
+            (70, 0),
+            (1, 1),
+            (64, 64),
+            (130, 65),
+            (2000, 900),
+        ] {
+            for _ in 0..10 {
+                let a = BigUInt::random_bits(x, &mut rng);
+                let b = BigUInt::random_bits(y, &mut rng);
+
+                let mut c = a.clone();
+                c += &b;
+                assert_eq!(c, &a + &b);
+                assert_eq!(a.clone() + b.clone(), c);
+                assert_eq!(&a + b.clone(), c);
+
+                let mut c = a.clone();
+                c -= &b;
+                assert_eq!(c, &a - &b);
+                assert_eq!(a.clone() - b.clone(), c);
+
+                let mut c = a.clone();
+                c *= &b;
+                assert_eq!(c, &a * &b);
+                assert_eq!(a.clone() * &b, c);
+                assert_eq!(&a * b.clone(), c);
+
+                let mut c = a.clone();
+                c &= &b;
+                assert_eq!(c, &a & &b);
+                assert_eq!(&a & b.clone(), c);
+
+                let mut c = a.clone();
+                c |= b.clone();
+                assert_eq!(c, &a | &b);
+                assert_eq!(&a | b.clone(), c);
+
+                let mut c = a.clone();
+                c ^= &b;
+                assert_eq!(c, &a ^ &b);
+                assert_eq!(a.clone() ^ b.clone(), c);
+                assert_eq!(&a ^ b.clone(), c);
+
+                if !b.is_zero() {
+                    let mut c = a.clone();
+                    c /= &b;
+                    assert_eq!(c, &a / &b);
+                    assert_eq!(a.clone() / b.clone(), c);
+
+                    let mut c = a.clone();
+                    c %= b.clone();
+                    assert_eq!(c, &a % &b);
+                    assert_eq!(&a % b.clone(), c);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prim_test() {
+        let mut rng = XorShift64::new(19);
+
+        for bits in [0, 1, 64, 65, 128, 129, 300] {
+            let a = BigUInt::random_bits(bits, &mut rng);
+
+            for m in [0, 1, 7, u64::MAX] {
+                let b = BigUInt::from(m);
+                assert_eq!(&a + m, &a + &b);
+                assert_eq!(m + &a, &a + &b);
+                assert_eq!(a.clone() - m, &a - &b);
+                assert_eq!(&a * m, &a * &b);
+                assert_eq!(m * a.clone(), &a * &b);
+
+                if m != 0 {
+                    assert_eq!(&a / m, &a / &b);
+                    assert_eq!(&a % m, &a % &b);
+                }
+            }
+
+            for m in [u64::MAX as u128 + 1, u128::MAX, 12345 << 70] {
+                let b = BigUInt::from_u64_digits(&[m as u64, (m >> 64) as u64]);
+
+                let mut c = a.clone();
+                c += m;
+                assert_eq!(c, &a + &b);
+
+                let mut c = a.clone();
+                c -= m;
+                assert_eq!(c, &a - &b);
+
+                let mut c = a.clone();
+                c *= m;
+                assert_eq!(c, &a * &b);
+
+                let mut c = a.clone();
+                c /= m;
+                assert_eq!(c, &a / &b);
+
+                let mut c = a.clone();
+                c %= m;
+                assert_eq!(c, &a % &b);
+            }
+        }
+
+        let mut a = BigUInt::from(250u8);
+        a += 10u8;
+        a *= 3u16;
+        a -= 80u32;
+        a /= 7u64;
+        a %= 100u128;
+        assert_eq!(a, BigUInt::from(0u64));
+
+        let v = [0u8; 12];
+        let a = BigUInt::from(30u64);
+        assert_eq!(&a + v.len(), BigUInt::from(42u64));
+        assert_eq!(v.len() * a.clone(), BigUInt::from(360u64));
+        assert_eq!(a % usize::MAX, BigUInt::from(30u64));
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn div_assign_zero_test() {
+        let mut a = BigUInt::from(7u64);
+        a /= 0u64;
+    }
+}
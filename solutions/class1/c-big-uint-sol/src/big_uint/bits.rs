@@ -0,0 +1,280 @@
+//! Bitwise operators, shifts and single-bit access for `BigUInt`.
+
+use std::ops;
+
+use super::{kernel, BigUInt, Limbs};
+
+impl BigUInt {
+    /// Returns whether bit `i` is set, counting from the least significant bit.
+    pub fn bit(&self, i: usize) -> bool {
+        self.inner
+            .get(i / 64)
+            .is_some_and(|x| x >> (i % 64) & 1 != 0)
+    }
+
+    /// Sets bit `i`, growing the number if needed.
+    pub fn set_bit(&mut self, i: usize) {
+        if self.inner.len() <= i / 64 {
+            self.inner.resize(i / 64 + 1, 0);
+        }
+        self.inner[i / 64] |= 1 << (i % 64);
+    }
+
+    /// Clears bit `i`.
+    pub fn clear_bit(&mut self, i: usize) {
+        if let Some(x) = self.inner.get_mut(i / 64) {
+            *x &= !(1 << (i % 64));
+            self.inner.trim();
+        }
+    }
+
+    /// Number of bits needed to represent the number; `0` for zero.
+    pub fn bit_length(&self) -> usize {
+        match self.inner.last() {
+            Some(x) => self.inner.len() * 64 - x.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.inner.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    /// Number of low zero bits, or `None` for zero.
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        let i = self.inner.iter().position(|&x| x != 0)?;
+        Some(i * 64 + self.inner[i].trailing_zeros() as usize)
+    }
+}
+
+impl<'a, T> ops::BitAnd<T> for &BigUInt
+where
+    T: Into<&'a BigUInt>,
+{
+    type Output = BigUInt;
+
+    /// Bitwise and between two numbers.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(0b1100u64);
+    /// let b = BigUInt::from(0b1010u64);
+    ///
+    /// assert_eq!(&a & &b, BigUInt::from(0b1000u64));
+    /// ```
+    fn bitand(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        BigUInt::from_limbs(
+            self.inner
+                .iter()
+                .zip(&rhs.inner)
+                .map(|(x, y)| x & y)
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+impl<'a, T> ops::BitOr<T> for &BigUInt
+where
+    T: Into<&'a BigUInt>,
+{
+    type Output = BigUInt;
+
+    /// Bitwise or between two numbers.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(0b1100u64);
+    /// let b = BigUInt::from(0b1010u64);
+    ///
+    /// assert_eq!(&a | &b, BigUInt::from(0b1110u64));
+    /// ```
+    fn bitor(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        let (long, short) = if self.inner.len() >= rhs.inner.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        let mut inner = long.inner.clone();
+        inner
+            .iter_mut()
+            .zip(&short.inner)
+            .for_each(|(x, y)| *x |= y);
+
+        BigUInt { inner }
+    }
+}
+
+impl<'a, T> ops::BitXor<T> for &BigUInt
+where
+    T: Into<&'a BigUInt>,
+{
+    type Output = BigUInt;
+
+    /// Bitwise xor between two numbers.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(0b1100u64);
+    /// let b = BigUInt::from(0b1010u64);
+    ///
+    /// assert_eq!(&a ^ &b, BigUInt::from(0b0110u64));
+    /// ```
+    fn bitxor(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        let (long, short) = if self.inner.len() >= rhs.inner.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        let mut inner = long.inner.clone();
+        inner
+            .iter_mut()
+            .zip(&short.inner)
+            .for_each(|(x, y)| *x ^= y);
+
+        BigUInt::from_limbs(inner)
+    }
+}
+
+impl ops::Shl<usize> for &BigUInt {
+    type Output = BigUInt;
+
+    /// Shift left by `rhs` bits; same as multiplying by `2^rhs`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(3u64);
+    ///
+    /// assert_eq!(&a << 65, BigUInt::from_str("110680464442257309696").unwrap());
+    /// ```
+    fn shl(self, rhs: usize) -> Self::Output {
+        if self.inner.is_empty() {
+            return BigUInt::zero();
+        }
+
+        let mut inner = vec![0; rhs / 64];
+        inner.extend_from_slice(&self.inner);
+
+        let carry = kernel::shl_bits(&mut inner[rhs / 64..], (rhs % 64) as u32);
+        if carry != 0 {
+            inner.push(carry);
+        }
+
+        BigUInt::from_limbs(inner)
+    }
+}
+
+impl ops::Shr<usize> for &BigUInt {
+    type Output = BigUInt;
+
+    /// Shift right by `rhs` bits; same as dividing by `2^rhs`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(100u64);
+    ///
+    /// assert_eq!(&a >> 3, BigUInt::from(12u64));
+    /// ```
+    fn shr(self, rhs: usize) -> Self::Output {
+        match self.inner.get(rhs / 64..) {
+            Some(high) => {
+                let mut inner = Limbs::from(high);
+                kernel::shr_bits(&mut inner, (rhs % 64) as u32);
+                BigUInt::from_limbs(inner)
+            }
+            None => BigUInt::zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::big_uint::BigUInt;
+
+    #[test]
+    fn bit_test() {
+        let mut a = BigUInt::zero();
+        assert_eq!(a.bit_length(), 0);
+        assert_eq!(a.trailing_zeros(), None);
+
+        a.set_bit(130);
+        a.set_bit(3);
+        assert!(a.bit(130) && a.bit(3) && !a.bit(4) && !a.bit(1000));
+        assert_eq!(a.bit_length(), 131);
+        assert_eq!(a.count_ones(), 2);
+        assert_eq!(a.trailing_zeros(), Some(3));
+
+        a.clear_bit(130);
+        assert_eq!(a, BigUInt::from(8u64));
+        a.clear_bit(3);
+        assert_eq!(a, BigUInt::zero());
+        a.clear_bit(500);
+        assert_eq!(a, BigUInt::zero());
+    }
+
+    #[test]
+    fn bitwise_test() {
+        fn case(a: &str, b: &str, and: &str, or: &str, xor: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            let b = BigUInt::from_str(b).unwrap();
+            assert_eq!(&a & &b, BigUInt::from_str(and).unwrap());
+            assert_eq!(&a | &b, BigUInt::from_str(or).unwrap());
+            assert_eq!(&a ^ &b, BigUInt::from_str(xor).unwrap());
+        }
+
+        case("12", "10", "8", "14", "6");
+        case(
+            "340282366920938463463374607431768211455",
+            "18446744073709551616",
+            "18446744073709551616",
+            "340282366920938463463374607431768211455",
+            "340282366920938463444927863358058659839",
+        );
+        case(
+            "18446744073709551617",
+            "18446744073709551617",
+            "18446744073709551617",
+            "18446744073709551617",
+            "0",
+        );
+    }
+
+    #[test]
+    fn shift_test() {
+        fn case(a: &str, n: usize, c: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            let c = BigUInt::from_str(c).unwrap();
+            assert_eq!(&a << n, c);
+            assert_eq!(&c >> n, a);
+        }
+
+        case("3", 0, "3");
+        case("3", 1, "6");
+        case("3", 64, "55340232221128654848");
+        case("3", 65, "110680464442257309696");
+        case(
+            "18446744073709551615",
+            63,
+            "170141183460469231722463931679029329920",
+        );
+        case("0", 100, "0");
+
+        let a = BigUInt::from_str("110680464442257309696").unwrap();
+        assert_eq!(&a >> 66, BigUInt::one());
+        assert_eq!(&a >> 67, BigUInt::zero());
+        assert_eq!(&a >> 1000, BigUInt::zero());
+    }
+}
@@ -0,0 +1,318 @@
+//! Byte-level conversions and binary encodings for `BigUInt`.
+//!
+//! Besides plain big and little endian bytes, two streaming formats are
+//! provided: unsigned LEB128, and a length-prefixed format where a LEB128
+//! byte count is followed by the little-endian bytes of the number.
+
+use std::io::{self, Read, Write};
+
+use super::BigUInt;
+
+impl BigUInt {
+    /// Big-endian bytes without leading zeros; `[0]` for zero.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(0x1234u64);
+    /// assert_eq!(a.to_bytes_be(), vec![0x12, 0x34]);
+    /// ```
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Little-endian bytes without trailing zeros; `[0]` for zero.
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = self
+            .inner
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        while bytes.len() > 1 && bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        if bytes.is_empty() {
+            bytes.push(0);
+        }
+
+        bytes
+    }
+
+    /// Number from big-endian bytes. Leading zeros are allowed.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from_bytes_be(&[0x00, 0x12, 0x34]);
+    /// assert_eq!(a, BigUInt::from(0x1234u64));
+    /// ```
+    pub fn from_bytes_be(bytes: &[u8]) -> BigUInt {
+        let inner = bytes
+            .rchunks(8)
+            .map(|c| c.iter().fold(0, |a, &b| a << 8 | b as u64))
+            .collect::<Vec<_>>();
+        BigUInt::from_limbs(inner)
+    }
+
+    /// Number from little-endian bytes. Trailing zeros are allowed.
+    pub fn from_bytes_le(bytes: &[u8]) -> BigUInt {
+        let inner = bytes
+            .chunks(8)
+            .map(|c| c.iter().rev().fold(0, |a, &b| a << 8 | b as u64))
+            .collect::<Vec<_>>();
+        BigUInt::from_limbs(inner)
+    }
+
+    /// Little-endian base `2^64` digits; empty for zero.
+    pub fn to_u64_digits(&self) -> Vec<u64> {
+        self.inner.to_vec()
+    }
+
+    /// Number from little-endian base `2^64` digits.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from_u64_digits(&[0, 1]);
+    /// assert_eq!(a, BigUInt::from_str("18446744073709551616").unwrap());
+    /// ```
+    pub fn from_u64_digits(digits: &[u64]) -> BigUInt {
+        BigUInt::from_limbs(digits.to_vec())
+    }
+
+    /// Unsigned LEB128 encoding: 7 bits per byte, least significant first,
+    /// with the high bit set on every byte but the last.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(624485u64);
+    /// assert_eq!(a.to_leb128(), vec![0xe5, 0x8e, 0x26]);
+    /// ```
+    pub fn to_leb128(&self) -> Vec<u8> {
+        let groups = self.bit_length().div_ceil(7).max(1);
+
+        (0..groups)
+            .map(|i| {
+                let b = self.bits_at(i * 7) as u8 & 0x7f;
+                if i + 1 < groups {
+                    b | 0x80
+                } else {
+                    b
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the LEB128 encoding of the number.
+    pub fn write_leb128(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(&self.to_leb128())
+    }
+
+    /// Reads a LEB128 encoded number, consuming exactly its bytes.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut r: &[u8] = &[0xe5, 0x8e, 0x26, 0xff];
+    /// let a = BigUInt::read_leb128(&mut r).unwrap();
+    /// assert_eq!(a, BigUInt::from(624485u64));
+    /// assert_eq!(r, [0xff]);
+    /// ```
+    pub fn read_leb128(r: &mut impl Read) -> io::Result<BigUInt> {
+        let mut inner = Vec::new();
+        let mut pos = 0;
+
+        loop {
+            let mut b = [0];
+            r.read_exact(&mut b)?;
+            let x = (b[0] & 0x7f) as u64;
+
+            if inner.len() <= pos / 64 + 1 {
+                inner.resize(pos / 64 + 2, 0);
+            }
+            inner[pos / 64] |= x << (pos % 64);
+            if pos % 64 > 57 {
+                inner[pos / 64 + 1] |= x >> (64 - pos % 64);
+            }
+
+            if b[0] & 0x80 == 0 {
+                return Ok(BigUInt::from_limbs(inner));
+            }
+            pos += 7;
+        }
+    }
+
+    /// Writes the number as its LEB128 byte count followed by its little-endian bytes.
+    /// Zero is a zero count with no bytes.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut buf = Vec::new();
+    /// BigUInt::from(0x1234u64).write_to(&mut buf).unwrap();
+    /// assert_eq!(buf, vec![2, 0x34, 0x12]);
+    /// ```
+    pub fn write_to(&self, w: &mut impl Write) -> io::Result<()> {
+        let bytes = if self.is_zero() {
+            Vec::new()
+        } else {
+            self.to_bytes_le()
+        };
+
+        BigUInt::from(bytes.len()).write_leb128(w)?;
+        w.write_all(&bytes)
+    }
+
+    /// Reads a number written by `write_to`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut r: &[u8] = &[2, 0x34, 0x12];
+    /// assert_eq!(BigUInt::read_from(&mut r).unwrap(), BigUInt::from(0x1234u64));
+    /// ```
+    pub fn read_from(r: &mut impl Read) -> io::Result<BigUInt> {
+        let len = BigUInt::read_leb128(r)?;
+        let len = match len.inner[..] {
+            [] => 0,
+            [x] => x,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "length prefix is too large",
+                ))
+            }
+        };
+
+        let mut bytes = Vec::new();
+        r.take(len).read_to_end(&mut bytes)?;
+        if (bytes.len() as u64) < len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        Ok(BigUInt::from_bytes_le(&bytes))
+    }
+
+    /// The 64 bits starting at bit `pos`, with zeros past the end.
+    fn bits_at(&self, pos: usize) -> u64 {
+        let (i, s) = (pos / 64, pos % 64);
+        let lo = self.inner.get(i).map_or(0, |x| x >> s);
+
+        if s == 0 {
+            lo
+        } else {
+            lo | self.inner.get(i + 1).map_or(0, |x| x << (64 - s))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::ErrorKind;
+    use std::str::FromStr;
+
+    use crate::big_uint::{BigUInt, XorShift64};
+
+    #[test]
+    fn bytes_test() {
+        fn case(a: &str, be: &[u8]) {
+            let a = BigUInt::from_str(a).unwrap();
+            let mut le = be.to_vec();
+            le.reverse();
+
+            assert_eq!(a.to_bytes_be(), be, "{a}");
+            assert_eq!(a.to_bytes_le(), le, "{a}");
+            assert_eq!(BigUInt::from_bytes_be(be), a);
+            assert_eq!(BigUInt::from_bytes_le(&le), a);
+        }
+
+        case("0", &[0]);
+        case("1", &[1]);
+        case("255", &[0xff]);
+        case("256", &[1, 0]);
+        case("0xffffffffffffffff", &[0xff; 8]);
+        case("0x10000000000000000", &[1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        case(
+            "0x0102030405060708090a0b0c0d0e0f10",
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+        );
+
+        assert_eq!(BigUInt::from_bytes_be(&[]), BigUInt::zero());
+        assert_eq!(BigUInt::from_bytes_be(&[0, 0, 0, 7]), BigUInt::from(7u64));
+        assert_eq!(BigUInt::from_bytes_le(&[7, 0, 0, 0]), BigUInt::from(7u64));
+    }
+
+    #[test]
+    fn u64_digits_test() {
+        let a = BigUInt::from_str("0x30000000000000002").unwrap();
+        assert_eq!(a.to_u64_digits(), vec![2, 3]);
+        assert_eq!(BigUInt::from_u64_digits(&[2, 3, 0, 0]), a);
+        assert!(BigUInt::zero().to_u64_digits().is_empty());
+    }
+
+    #[test]
+    fn leb128_test() {
+        fn case(a: &str, c: &[u8]) {
+            let a = BigUInt::from_str(a).unwrap();
+            assert_eq!(a.to_leb128(), c, "{a}");
+
+            let mut r = c;
+            assert_eq!(BigUInt::read_leb128(&mut r).unwrap(), a);
+            assert!(r.is_empty());
+        }
+
+        case("0", &[0]);
+        case("1", &[1]);
+        case("127", &[0x7f]);
+        case("128", &[0x80, 0x01]);
+        case("624485", &[0xe5, 0x8e, 0x26]);
+        case(
+            "0xffffffffffffffff",
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+        );
+        case(
+            "0x10000000000000000",
+            &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x02],
+        );
+
+        // redundant high groups are accepted
+        let mut r: &[u8] = &[0x81, 0x80, 0x00];
+        assert_eq!(BigUInt::read_leb128(&mut r).unwrap(), BigUInt::one());
+
+        let mut r: &[u8] = &[0x80, 0x80];
+        let e = BigUInt::read_leb128(&mut r).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn stream_test() {
+        let mut rng = XorShift64::new(13);
+        let values =
+            [0, 1, 63, 64, 65, 127, 128, 1000].map(|bits| BigUInt::random_bits(bits, &mut rng));
+
+        let mut buf = Vec::new();
+        for a in &values {
+            a.write_to(&mut buf).unwrap();
+            a.write_leb128(&mut buf).unwrap();
+        }
+
+        let mut r = &buf[..];
+        for a in &values {
+            assert_eq!(BigUInt::read_from(&mut r).unwrap(), *a);
+            assert_eq!(BigUInt::read_leb128(&mut r).unwrap(), *a);
+        }
+        assert!(r.is_empty());
+
+        let mut zero = Vec::new();
+        BigUInt::zero().write_to(&mut zero).unwrap();
+        assert_eq!(zero, vec![0]);
+
+        let mut r: &[u8] = &[3, 1, 2];
+        let e = BigUInt::read_from(&mut r).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::UnexpectedEof);
+    }
+}
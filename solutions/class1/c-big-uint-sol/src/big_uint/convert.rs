@@ -0,0 +1,213 @@
+//! Conversions between `BigUInt` and primitive integers and floats.
+
+use super::{BigUInt, TryFromBigUIntError};
+
+impl BigUInt {
+    /// Nearest `f64`, ties to even; `f64::INFINITY` if it is too large.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from_str("9007199254740993").unwrap();
+    /// assert_eq!(a.to_f64(), 9007199254740992.0);
+    /// ```
+    pub fn to_f64(&self) -> f64 {
+        let bits = self.bit_length();
+        if bits > 1024 {
+            return f64::INFINITY;
+        }
+
+        let (m, shift) = self.top_u64();
+        // `m` is rounded once; the scaling by a power of two is exact.
+        m as f64 * f64::from_bits((1023 + shift as u64) << 52)
+    }
+
+    /// Nearest `f32`, ties to even; `f32::INFINITY` if it is too large.
+    pub fn to_f32(&self) -> f32 {
+        let bits = self.bit_length();
+        if bits > 128 {
+            return f32::INFINITY;
+        }
+
+        let (m, shift) = self.top_u64();
+        m as f32 * f32::from_bits((127 + shift as u32) << 23)
+    }
+
+    /// The top 64 bits and the shift that brings them back to the number.
+    /// Any dropped non-zero bit is folded into the lowest bit, which is
+    /// enough for a float conversion of `m` to round like the whole number.
+    fn top_u64(&self) -> (u64, usize) {
+        let bits = self.bit_length();
+        if bits <= 64 {
+            return (self.inner.first().copied().unwrap_or(0), 0);
+        }
+
+        let shift = bits - 64;
+        let m = (self >> shift).inner[0];
+        let sticky = self.trailing_zeros().is_some_and(|z| z < shift);
+        (m | sticky as u64, shift)
+    }
+}
+
+impl From<u128> for BigUInt {
+    fn from(x: u128) -> Self {
+        BigUInt::from_limbs(&[x as u64, (x >> 64) as u64][..])
+    }
+}
+
+impl TryFrom<f64> for BigUInt {
+    type Error = TryFromBigUIntError;
+
+    /// Integral, non-negative float to number.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// assert_eq!(BigUInt::try_from(1e20).unwrap().to_string(), "100000000000000000000");
+    /// assert!(BigUInt::try_from(0.5).is_err());
+    /// ```
+    fn try_from(x: f64) -> Result<Self, Self::Error> {
+        if x.is_nan() {
+            return Err(TryFromBigUIntError::NotInteger);
+        }
+        if x.is_infinite() || x < 0.0 {
+            return Err(TryFromBigUIntError::OutOfRange);
+        }
+        if x.fract() != 0.0 {
+            return Err(TryFromBigUIntError::NotInteger);
+        }
+        if x == 0.0 {
+            return Ok(BigUInt::zero());
+        }
+
+        // An integral float is at least 1, so it is normal.
+        let bits = x.to_bits();
+        let exp = (bits >> 52 & 0x7ff) as usize;
+        let m = BigUInt::from(bits & ((1 << 52) - 1) | 1 << 52);
+
+        Ok(if exp >= 1075 {
+            &m << (exp - 1075)
+        } else {
+            &m >> (1075 - exp)
+        })
+    }
+}
+
+macro_rules! big_uint_try_into_impl {
+    ($($t:ty),*) => {$(
+        impl TryFrom<&BigUInt> for $t {
+            type Error = TryFromBigUIntError;
+
+            fn try_from(x: &BigUInt) -> Result<Self, Self::Error> {
+                let v = match x.inner[..] {
+                    [] => 0,
+                    [lo] => lo as u128,
+                    [lo, hi] => (hi as u128) << 64 | lo as u128,
+                    _ => return Err(TryFromBigUIntError::OutOfRange),
+                };
+                <$t>::try_from(v).map_err(|_| TryFromBigUIntError::OutOfRange)
+            }
+        }
+    )*};
+}
+
+big_uint_try_into_impl!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::big_uint::{BigUInt, TryFromBigUIntError};
+
+    #[test]
+    fn int_test() {
+        let a = BigUInt::from(u128::MAX);
+        assert_eq!(a.to_string(), "340282366920938463463374607431768211455");
+        assert_eq!(u128::try_from(&a), Ok(u128::MAX));
+        assert_eq!(u64::try_from(&a), Err(TryFromBigUIntError::OutOfRange));
+        assert_eq!(i128::try_from(&a), Err(TryFromBigUIntError::OutOfRange));
+        assert_eq!(BigUInt::from(5u128), BigUInt::from(5u64));
+        assert_eq!(BigUInt::from(0u128), BigUInt::zero());
+
+        let b = BigUInt::from(255u64);
+        assert_eq!(u8::try_from(&b), Ok(255));
+        assert_eq!(i8::try_from(&b), Err(TryFromBigUIntError::OutOfRange));
+        assert_eq!(i16::try_from(&b), Ok(255));
+        assert_eq!(usize::try_from(&BigUInt::zero()), Ok(0));
+
+        let c = BigUInt::from(i64::MAX as u64);
+        assert_eq!(i64::try_from(&c), Ok(i64::MAX));
+        assert!(i64::try_from(&(&c + &BigUInt::one())).is_err());
+
+        let d = &BigUInt::one() << 128;
+        assert!(u128::try_from(&d).is_err());
+    }
+
+    #[test]
+    fn to_float_test() {
+        fn case(a: &str, f: f64, g: f32) {
+            let a = BigUInt::from_str(a).unwrap();
+            assert_eq!(a.to_f64(), f, "{a}");
+            assert_eq!(a.to_f32(), g, "{a}");
+        }
+
+        case("0", 0.0, 0.0);
+        case("1", 1.0, 1.0);
+        case("16777217", 16777217.0, 16777216.0);
+        case("16777219", 16777219.0, 16777220.0);
+        // 2^53 + 1 ties to even, 2^53 + 3 ties up to the even 2^53 + 4
+        case("9007199254740993", 9007199254740992.0, 9007199254740992.0);
+        case("9007199254740995", 9007199254740996.0, 9007199254740992.0);
+        // 2^64 + 2^11 ties down to even; a low set bit breaks such a tie upward
+        case("0x10000000000000800", 18446744073709551616.0, 2f32.powi(64));
+        case(
+            "0x10000000000000800000000000000001",
+            2.126764793255866e37,
+            2f32.powi(124),
+        );
+        case(
+            "0x100000000000008000000000001",
+            2.0282409603651675e31,
+            2f32.powi(104),
+        );
+
+        let max = BigUInt::try_from(f64::MAX).unwrap();
+        assert_eq!(max.to_f64(), f64::MAX);
+        assert_eq!((&max + &(&BigUInt::one() << 970)).to_f64(), f64::INFINITY);
+        assert_eq!((&max + &(&BigUInt::one() << 969)).to_f64(), f64::MAX);
+        assert_eq!((&BigUInt::one() << 2000).to_f64(), f64::INFINITY);
+    }
+
+    #[test]
+    fn try_from_float_test() {
+        assert_eq!(BigUInt::try_from(0.0), Ok(BigUInt::zero()));
+        assert_eq!(BigUInt::try_from(-0.0), Ok(BigUInt::zero()));
+        assert_eq!(BigUInt::try_from(42.0), Ok(BigUInt::from(42u64)));
+        assert_eq!(
+            BigUInt::try_from(2f64.powi(100)),
+            Ok(&BigUInt::one() << 100)
+        );
+        assert_eq!(
+            BigUInt::try_from(9007199254740991.0),
+            Ok(BigUInt::from(9007199254740991u64))
+        );
+
+        assert_eq!(BigUInt::try_from(0.5), Err(TryFromBigUIntError::NotInteger));
+        assert_eq!(
+            BigUInt::try_from(f64::NAN),
+            Err(TryFromBigUIntError::NotInteger)
+        );
+        assert_eq!(
+            BigUInt::try_from(-1.0),
+            Err(TryFromBigUIntError::OutOfRange)
+        );
+        assert_eq!(
+            BigUInt::try_from(f64::INFINITY),
+            Err(TryFromBigUIntError::OutOfRange)
+        );
+
+        for x in [1.0, 3.0e15, 1.5e300, f64::MAX] {
+            assert_eq!(BigUInt::try_from(x).unwrap().to_f64(), x);
+        }
+    }
+}
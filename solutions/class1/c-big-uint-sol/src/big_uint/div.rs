@@ -0,0 +1,149 @@
+//! Division kernels.
+//!
+//! Single-limb divisors take a simple one-pass fast path; everything else
+//! goes through Knuth's Algorithm D (TAOCP vol. 2, 4.3.1) with `u128` trial
+//! quotients.
+
+use super::kernel;
+
+/// Returns the normalized quotient and remainder of `a / b`.
+/// `b` must not be zero.
+pub(crate) fn div_rem(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let a = &a[..kernel::trimmed_len(a)];
+    let b = &b[..kernel::trimmed_len(b)];
+    assert!(!b.is_empty(), "division by zero");
+
+    if kernel::cmp(a, b).is_lt() {
+        return (Vec::new(), a.to_vec());
+    }
+
+    if b.len() == 1 {
+        let mut q = a.to_vec();
+        let r = kernel::div_rem_limb(&mut q, b[0]);
+        kernel::trim(&mut q);
+        return (q, if r == 0 { Vec::new() } else { vec![r] });
+    }
+
+    knuth_d(a, b)
+}
+
+/// Algorithm D for `a >= b` and `b.len() >= 2`.
+fn knuth_d(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let n = b.len();
+    let m = a.len() - n;
+
+    // D1: normalize so that the top bit of the divisor is set.
+    let s = b[n - 1].leading_zeros();
+    let mut v = b.to_vec();
+    kernel::shl_bits(&mut v, s);
+    let mut u = a.to_vec();
+    let top = kernel::shl_bits(&mut u, s);
+    u.push(top);
+
+    let (v1, v2) = (v[n - 1] as u128, v[n - 2] as u128);
+    let mut q = vec![0; m + 1];
+    let mut t = vec![0; n + 1];
+
+    for j in (0..=m).rev() {
+        // D3: estimate the quotient digit from the top two limbs.
+        let num = ((u[j + n] as u128) << 64) | u[j + n - 1] as u128;
+        let mut qhat = num / v1;
+        let mut rhat = num % v1;
+
+        while qhat > u64::MAX as u128 || qhat * v2 > ((rhat << 64) | u[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += v1;
+            if rhat > u64::MAX as u128 {
+                break;
+            }
+        }
+
+        // D4: multiply and subtract.
+        t[..n].copy_from_slice(&v);
+        t[n] = kernel::mul_limb(&mut t[..n], qhat as u64);
+        let borrow = kernel::sub_assign(&mut u[j..=j + n], &t);
+
+        // D6: the estimate was one too large, add the divisor back.
+        if borrow {
+            qhat -= 1;
+            kernel::add_assign(&mut u[j..=j + n], &v);
+        }
+
+        q[j] = qhat as u64;
+    }
+
+    // D8: unnormalize the remainder.
+    u.truncate(n);
+    kernel::shr_bits(&mut u, s);
+    kernel::trim(&mut u);
+    kernel::trim(&mut q);
+
+    (q, u)
+}
+
+#[cfg(test)]
+mod test {
+    use super::div_rem;
+    use crate::big_uint::{kernel, mul};
+
+    fn limbs(seed: &mut u64, n: usize) -> Vec<u64> {
+        (0..n)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 7;
+                *seed ^= *seed << 17;
+                *seed
+            })
+            .collect()
+    }
+
+    fn check(a: &[u64], b: &[u64]) {
+        let (q, r) = div_rem(a, b);
+        assert!(kernel::cmp(&r, b).is_lt(), "{a:?} / {b:?}");
+
+        let mut back = mul::mul(&q, b);
+        back.resize(back.len().max(r.len()) + 1, 0);
+        kernel::add_assign(&mut back, &r);
+        assert!(kernel::cmp(&back, a).is_eq(), "{a:?} / {b:?}");
+    }
+
+    #[test]
+    fn div_rem_random_test() {
+        let mut seed = 0x9e37_79b9_7f4a_7c15;
+
+        for (n, m) in [
+            (1, 1),
+            (5, 1),
+            (2, 2),
+            (7, 3),
+            (40, 17),
+            (100, 99),
+            (300, 120),
+        ] {
+            let a = limbs(&mut seed, n);
+            let b = limbs(&mut seed, m);
+            check(&a, &b);
+            check(&b, &a);
+        }
+    }
+
+    #[test]
+    fn div_rem_edge_test() {
+        const H: u64 = 1 << 63;
+
+        check(&[0, 0, H, H - 1], &[1, 0, H]);
+        check(&[3, 0, H], &[1, 0, H >> 2]);
+        check(&[0, 0, H, H - 1], &[u64::MAX, H - 1]);
+        check(&[u64::MAX; 8], &[u64::MAX; 3]);
+        check(&[0, 0, 0, 1], &[u64::MAX, u64::MAX]);
+        check(&[0, u64::MAX, u64::MAX], &[1, u64::MAX]);
+        check(&[7, 7, 7], &[7, 7, 7]);
+    }
+
+    #[test]
+    fn div_rem_single_limb_test() {
+        assert_eq!(div_rem(&[100], &[7]), (vec![14], vec![2]));
+        assert_eq!(div_rem(&[0, 1], &[2]), (vec![1 << 63], vec![]));
+        assert_eq!(div_rem(&[5], &[0, 1]), (vec![], vec![5]));
+    }
+}
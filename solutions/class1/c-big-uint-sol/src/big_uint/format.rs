@@ -0,0 +1,320 @@
+//! Decimal formatting for people: digit grouping, padding and scientific
+//! notation.
+//!
+//! `Display` already honors width, fill, alignment and `+` through
+//! `Formatter::pad_integral`; `FormatSpec` adds what the standard flags
+//! cannot express and builds a `String` directly.
+
+use std::fmt::Alignment;
+
+use super::BigUInt;
+
+/// How `BigUInt::format_with` lays out a number.
+///
+/// ## Examples
+///
+/// ```
+/// let a = BigUInt::from(1234567u64);
+/// assert_eq!(a.format_with(FormatSpec::new().separator(',')), "1,234,567");
+/// assert_eq!(a.format_with(FormatSpec::new().scientific(3)), "1.23e6");
+/// assert_eq!(a.format_with(FormatSpec::new().width(10).fill('*')), "***1234567");
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct FormatSpec {
+    separator: Option<char>,
+    group: usize,
+    width: usize,
+    fill: char,
+    align: Alignment,
+    zero_pad: bool,
+    plus: bool,
+    scientific: Option<usize>,
+}
+
+impl Default for FormatSpec {
+    fn default() -> Self {
+        FormatSpec {
+            separator: None,
+            group: 3,
+            width: 0,
+            fill: ' ',
+            align: Alignment::Right,
+            zero_pad: false,
+            plus: false,
+            scientific: None,
+        }
+    }
+}
+
+impl FormatSpec {
+    /// Plain decimal digits, like `to_string`.
+    pub fn new() -> FormatSpec {
+        FormatSpec::default()
+    }
+
+    /// Character put between groups of digits, counted from the right.
+    pub fn separator(mut self, c: char) -> Self {
+        self.separator = Some(c);
+        self
+    }
+
+    /// Number of digits in a group; 3 by default.
+    ///
+    /// Panics if `n` is zero.
+    pub fn group_size(mut self, n: usize) -> Self {
+        assert!(n > 0, "group size must be positive");
+        self.group = n;
+        self
+    }
+
+    /// Minimum number of characters, sign included.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Character used to reach the width; a space by default.
+    pub fn fill(mut self, c: char) -> Self {
+        self.fill = c;
+        self
+    }
+
+    /// Where the number goes within the width; right by default.
+    pub fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Whether to reach the width with zeros between the sign and the
+    /// digits, like `{:+08}`, instead of with the fill. With a separator the
+    /// zeros are grouped too, like `0,001,234`, and a zero is added rather
+    /// than starting with a separator, which can exceed the width by one.
+    pub fn zero_pad(mut self, zero_pad: bool) -> Self {
+        self.zero_pad = zero_pad;
+        self
+    }
+
+    /// Whether non-negative numbers get a `+`.
+    pub fn plus_sign(mut self, plus: bool) -> Self {
+        self.plus = plus;
+        self
+    }
+
+    /// Scientific notation with `digits` significant digits, like
+    /// `1.2346e45`. Digits are not grouped in this notation.
+    ///
+    /// Panics if `digits` is zero.
+    pub fn scientific(mut self, digits: usize) -> Self {
+        assert!(digits > 0, "scientific notation needs a significant digit");
+        self.scientific = Some(digits);
+        self
+    }
+
+    /// `mag`, with a `-` in front if `neg`, laid out by this spec.
+    pub(crate) fn format(&self, neg: bool, mag: &BigUInt) -> String {
+        let sign = match (neg, self.plus) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        };
+        let digits = mag.to_string();
+        let body = match (self.scientific, self.separator) {
+            (Some(n), _) => scientific(&digits, n),
+            (None, Some(sep)) if self.zero_pad => {
+                // Enough digits that, once grouped, they reach the width.
+                let mut n = digits.len();
+                while sign.len() + n + (n - 1) / self.group < self.width {
+                    n += 1;
+                }
+                group(&format!("{digits:0>n$}"), self.group, sep)
+            }
+            (None, Some(sep)) => group(&digits, self.group, sep),
+            (None, None) => digits,
+        };
+
+        let len = sign.len() + body.chars().count();
+        let pad = self.width.saturating_sub(len);
+        if self.zero_pad {
+            return format!("{sign}{}{body}", "0".repeat(pad));
+        }
+
+        let (left, right) = match self.align {
+            Alignment::Left => (0, pad),
+            Alignment::Right => (pad, 0),
+            Alignment::Center => (pad / 2, pad - pad / 2),
+        };
+        let fill = |n| self.fill.to_string().repeat(n);
+        format!("{}{sign}{body}{}", fill(left), fill(right))
+    }
+}
+
+/// `digits` with `sep` before every `size` digits counted from the right.
+fn group(digits: &str, size: usize, sep: char) -> String {
+    let mut out = String::with_capacity(digits.len() + digits.len() / size);
+
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(size) {
+            out.push(sep);
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+/// Decimal `digits` as `d.ddde<exp>` with `n` significant digits, rounded
+/// half to even.
+fn scientific(digits: &str, n: usize) -> String {
+    let mut exp = digits.len() - 1;
+    let mut kept = digits.as_bytes()[..n.min(digits.len())].to_vec();
+    kept.resize(n, b'0');
+
+    let rest = digits.as_bytes().get(n..).unwrap_or_default();
+    let round_up = match rest.first() {
+        Some(b'6'..=b'9') => true,
+        Some(b'5') => rest[1..].iter().any(|&d| d != b'0') || kept[n - 1] % 2 == 1,
+        _ => false,
+    };
+
+    if round_up {
+        match kept.iter().rposition(|&d| d != b'9') {
+            Some(i) => {
+                kept[i] += 1;
+                kept[i + 1..].fill(b'0');
+            }
+            // All nines: 9.99e5 rounds to 1.00e6.
+            None => {
+                kept.fill(b'0');
+                kept[0] = b'1';
+                exp += 1;
+            }
+        }
+    }
+
+    let kept = String::from_utf8(kept).unwrap();
+    match kept.split_at(1) {
+        (lead, "") => format!("{lead}e{exp}"),
+        (lead, frac) => format!("{lead}.{frac}e{exp}"),
+    }
+}
+
+impl BigUInt {
+    /// Decimal string of the number laid out by `spec`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(10u64).pow(45) * 12345678u64;
+    /// assert_eq!(a.format_with(FormatSpec::new().scientific(5)), "1.2346e52");
+    /// ```
+    pub fn format_with(&self, spec: FormatSpec) -> String {
+        spec.format(false, self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt::Alignment;
+    use std::str::FromStr;
+
+    use crate::big_int::BigInt;
+    use crate::big_uint::BigUInt;
+
+    use super::FormatSpec;
+
+    #[test]
+    fn group_test() {
+        fn case(a: &str, spec: FormatSpec, c: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            assert_eq!(a.format_with(spec), c, "{a}");
+        }
+
+        let commas = FormatSpec::new().separator(',');
+        case("0", commas, "0");
+        case("999", commas, "999");
+        case("1000", commas, "1,000");
+        case("123456", commas, "123,456");
+        case("1234567", commas, "1,234,567");
+        case(
+            "123456789012345678901234567890123456789012345678901234567890",
+            commas,
+            "123,456,789,012,345,678,901,234,567,890,123,456,789,012,345,678,901,234,567,890",
+        );
+        case("12345678", commas.group_size(4), "1234,5678");
+        case(
+            "1234567",
+            FormatSpec::new().separator('\u{2009}'),
+            "1\u{2009}234\u{2009}567",
+        );
+        case("1234567", FormatSpec::new().group_size(2), "1234567");
+    }
+
+    #[test]
+    fn pad_test() {
+        fn case(a: i64, spec: FormatSpec, c: &str) {
+            assert_eq!(BigInt::from(a).format_with(spec), c, "{a}");
+        }
+
+        let w = FormatSpec::new().width(8);
+        case(42, w, "      42");
+        case(-42, w.align(Alignment::Left), "-42     ");
+        case(42, w.align(Alignment::Center).fill('*'), "***42***");
+        case(-42, w.zero_pad(true), "-0000042");
+        case(42, w.zero_pad(true).plus_sign(true), "+0000042");
+        case(0, w.plus_sign(true), "      +0");
+        case(-1234567, w.separator(','), "-1,234,567");
+
+        let z = w.separator(',').zero_pad(true);
+        case(1234567, z.width(12), "0,001,234,567");
+        case(1234567, z.width(13), "0,001,234,567");
+        case(1234567, z.width(10), "01,234,567");
+        case(1234567, z.width(4), "1,234,567");
+        case(-1234567, z.width(12), "-001,234,567");
+        case(0, z.width(6).plus_sign(true), "+0,000");
+        case(42, z.width(7).group_size(2), "0,00,42");
+    }
+
+    #[test]
+    fn scientific_test() {
+        fn case(a: &str, n: usize, c: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            assert_eq!(a.format_with(FormatSpec::new().scientific(n)), c, "{a} {n}");
+        }
+
+        case("0", 1, "0e0");
+        case("0", 3, "0.00e0");
+        case("7", 4, "7.000e0");
+        case("1234567", 3, "1.23e6");
+        case("1234567", 1, "1e6");
+        case("1234567", 7, "1.234567e6");
+        case("1234567", 10, "1.234567000e6");
+        case("1235", 3, "1.24e3");
+        case("1245", 3, "1.24e3");
+        case("1245001", 3, "1.25e6");
+        case("9995", 3, "1.00e4");
+        case("99999", 1, "1e5");
+        case(
+            "1234567890123456789012345678901234567890123456",
+            5,
+            "1.2346e45",
+        );
+
+        let a = -BigInt::from_str("98765").unwrap();
+        let spec = FormatSpec::new().scientific(2).width(9);
+        assert_eq!(a.format_with(spec), "   -9.9e4");
+    }
+
+    #[test]
+    fn display_test() {
+        let a = BigUInt::from(1234u64);
+        assert_eq!(format!("{a:>8}"), "    1234");
+        assert_eq!(format!("{a:<8}|"), "1234    |");
+        assert_eq!(format!("{a:*^8}"), "**1234**");
+        assert_eq!(format!("{a:+08}"), "+0001234");
+        assert_eq!(format!("{a:2}"), "1234");
+
+        let b = BigInt::from(-1234);
+        assert_eq!(format!("{b:>8}"), "   -1234");
+        assert_eq!(format!("{b:08}"), "-0001234");
+    }
+}
@@ -0,0 +1,182 @@
+//! Limb-level kernels.
+//!
+//! Every function here works on little-endian `u64` slices, the same layout
+//! as `BigUInt::inner`, so the algorithms can be shared without allocating
+//! intermediate `BigUInt` values.
+
+use std::cmp::Ordering;
+use std::iter;
+
+/// Remove high zero limbs so the vector is in normalized form.
+pub(crate) fn trim(v: &mut Vec<u64>) {
+    while let Some(&0) = v.last() {
+        v.pop();
+    }
+}
+
+/// Length of the slice without its high zero limbs.
+pub(crate) fn trimmed_len(a: &[u64]) -> usize {
+    a.iter().rposition(|&x| x != 0).map_or(0, |i| i + 1)
+}
+
+/// Compare two slices as numbers. High zero limbs are ignored.
+pub(crate) fn cmp(a: &[u64], b: &[u64]) -> Ordering {
+    let a = &a[..trimmed_len(a)];
+    let b = &b[..trimmed_len(b)];
+
+    a.len().cmp(&b.len()).then_with(|| {
+        iter::zip(a.iter().rev(), b.iter().rev())
+            .map(|(x, y)| x.cmp(y))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    })
+}
+
+/// `a += b` where `a.len() >= b.len()`; returns the carry out of `a`.
+pub(crate) fn add_assign(a: &mut [u64], b: &[u64]) -> bool {
+    debug_assert!(a.len() >= b.len());
+    let mut carry = false;
+
+    for (x, y) in a.iter_mut().zip(b) {
+        let (s, c1) = x.overflowing_add(*y);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        *x = s;
+        carry = c1 | c2;
+    }
+
+    if carry {
+        add_limb(&mut a[b.len()..], 1)
+    } else {
+        false
+    }
+}
+
+/// `a += m`; returns the carry out of `a`.
+pub(crate) fn add_limb(a: &mut [u64], m: u64) -> bool {
+    let mut carry = m;
+
+    for x in a.iter_mut() {
+        if carry == 0 {
+            break;
+        }
+        let (s, c) = x.overflowing_add(carry);
+        *x = s;
+        carry = c as u64;
+    }
+
+    carry != 0
+}
+
+/// `a -= b` where `a.len() >= b.len()`; returns the borrow out of `a`.
+pub(crate) fn sub_assign(a: &mut [u64], b: &[u64]) -> bool {
+    debug_assert!(a.len() >= b.len());
+    let mut borrow = false;
+
+    for (x, y) in a.iter_mut().zip(b) {
+        let (s, b1) = x.overflowing_sub(*y);
+        let (s, b2) = s.overflowing_sub(borrow as u64);
+        *x = s;
+        borrow = b1 | b2;
+    }
+
+    if borrow {
+        sub_limb(&mut a[b.len()..], 1)
+    } else {
+        false
+    }
+}
+
+/// `a -= m`; returns the borrow out of `a`.
+pub(crate) fn sub_limb(a: &mut [u64], m: u64) -> bool {
+    let mut borrow = m;
+
+    for x in a.iter_mut() {
+        if borrow == 0 {
+            break;
+        }
+        let (s, b) = x.overflowing_sub(borrow);
+        *x = s;
+        borrow = b as u64;
+    }
+
+    borrow != 0
+}
+
+/// `acc[..a.len()] += a * m`; returns the limb that carries out of that range.
+pub(crate) fn mul_limb_add(acc: &mut [u64], a: &[u64], m: u64) -> u64 {
+    debug_assert!(acc.len() >= a.len());
+    let mut carry = 0u64;
+
+    for (x, y) in acc.iter_mut().zip(a) {
+        let t = *x as u128 + *y as u128 * m as u128 + carry as u128;
+        *x = t as u64;
+        carry = (t >> 64) as u64;
+    }
+
+    carry
+}
+
+/// `a *= m`; returns the limb that carries out of `a`.
+pub(crate) fn mul_limb(a: &mut [u64], m: u64) -> u64 {
+    let mut carry = 0u64;
+
+    for x in a.iter_mut() {
+        let t = *x as u128 * m as u128 + carry as u128;
+        *x = t as u64;
+        carry = (t >> 64) as u64;
+    }
+
+    carry
+}
+
+/// `a /= d`; returns the remainder. `d` must not be zero.
+pub(crate) fn div_rem_limb(a: &mut [u64], d: u64) -> u64 {
+    let mut rem = 0u64;
+
+    for x in a.iter_mut().rev() {
+        let t = ((rem as u128) << 64) | *x as u128;
+        *x = (t / d as u128) as u64;
+        rem = (t % d as u128) as u64;
+    }
+
+    rem
+}
+
+/// `a mod d` without changing `a`. `d` must not be zero.
+pub(crate) fn rem_limb(a: &[u64], d: u64) -> u64 {
+    a.iter().rev().fold(0, |rem, &x| {
+        ((((rem as u128) << 64) | x as u128) % d as u128) as u64
+    })
+}
+
+/// `a <<= s` for `s < 64`; returns the bits shifted out of the top limb.
+pub(crate) fn shl_bits(a: &mut [u64], s: u32) -> u64 {
+    if s == 0 {
+        return 0;
+    }
+
+    let mut carry = 0u64;
+
+    for x in a.iter_mut() {
+        let t = *x >> (64 - s);
+        *x = (*x << s) | carry;
+        carry = t;
+    }
+
+    carry
+}
+
+/// `a >>= s` for `s < 64`; the bits shifted out of the bottom limb are dropped.
+pub(crate) fn shr_bits(a: &mut [u64], s: u32) {
+    if s == 0 {
+        return;
+    }
+
+    let mut carry = 0u64;
+
+    for x in a.iter_mut().rev() {
+        let t = *x << (64 - s);
+        *x = (*x >> s) | carry;
+        carry = t;
+    }
+}
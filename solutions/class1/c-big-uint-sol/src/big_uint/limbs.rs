@@ -0,0 +1,195 @@
+//! Limb storage for `BigUInt` with room for two limbs inline.
+//!
+//! Most numbers in practice fit in one or two limbs, so they are kept in a
+//! small array and never touch the heap. Longer numbers spill into a `Vec`,
+//! which is kept on shrinking so that in-place operations can reuse it.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// Number of limbs stored without a heap allocation.
+const INLINE: usize = 2;
+
+/// Little-endian limbs, usable as a slice through `Deref`.
+#[derive(Eq)]
+pub(crate) enum Limbs {
+    Inline(u8, [u64; INLINE]),
+    Heap(Vec<u64>),
+}
+
+impl Limbs {
+    pub(crate) const fn new() -> Limbs {
+        Limbs::Inline(0, [0; INLINE])
+    }
+
+    pub(crate) fn push(&mut self, x: u64) {
+        match self {
+            Limbs::Inline(len, buf) if (*len as usize) < INLINE => {
+                buf[*len as usize] = x;
+                *len += 1;
+            }
+            Limbs::Inline(_, buf) => {
+                let mut v = Vec::with_capacity(2 * INLINE);
+                v.extend_from_slice(buf);
+                v.push(x);
+                *self = Limbs::Heap(v);
+            }
+            Limbs::Heap(v) => v.push(x),
+        }
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<u64> {
+        match self {
+            Limbs::Inline(0, _) => None,
+            Limbs::Inline(len, buf) => {
+                *len -= 1;
+                Some(buf[*len as usize])
+            }
+            Limbs::Heap(v) => v.pop(),
+        }
+    }
+
+    pub(crate) fn resize(&mut self, n: usize, x: u64) {
+        match self {
+            Limbs::Inline(len, buf) if n <= INLINE => {
+                buf[(*len as usize).min(n)..n].fill(x);
+                *len = n as u8;
+            }
+            Limbs::Inline(..) => {
+                let mut v = Vec::with_capacity(n);
+                v.extend_from_slice(&self[..]);
+                v.resize(n, x);
+                *self = Limbs::Heap(v);
+            }
+            Limbs::Heap(v) => v.resize(n, x),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        match self {
+            Limbs::Inline(len, _) => *len = 0,
+            Limbs::Heap(v) => v.clear(),
+        }
+    }
+
+    /// Remove high zero limbs so the number is in normalized form.
+    pub(crate) fn trim(&mut self) {
+        while let Some(&0) = self.last() {
+            self.pop();
+        }
+    }
+}
+
+impl Deref for Limbs {
+    type Target = [u64];
+
+    fn deref(&self) -> &[u64] {
+        match self {
+            Limbs::Inline(len, buf) => &buf[..*len as usize],
+            Limbs::Heap(v) => v,
+        }
+    }
+}
+
+impl DerefMut for Limbs {
+    fn deref_mut(&mut self) -> &mut [u64] {
+        match self {
+            Limbs::Inline(len, buf) => &mut buf[..*len as usize],
+            Limbs::Heap(v) => v,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Limbs {
+    type Item = &'a u64;
+    type IntoIter = std::slice::Iter<'a, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl From<Vec<u64>> for Limbs {
+    fn from(v: Vec<u64>) -> Self {
+        if v.len() <= INLINE {
+            Limbs::from(&v[..])
+        } else {
+            Limbs::Heap(v)
+        }
+    }
+}
+
+impl From<&[u64]> for Limbs {
+    fn from(s: &[u64]) -> Self {
+        if s.len() <= INLINE {
+            let mut buf = [0; INLINE];
+            buf[..s.len()].copy_from_slice(s);
+            Limbs::Inline(s.len() as u8, buf)
+        } else {
+            Limbs::Heap(s.to_vec())
+        }
+    }
+}
+
+impl Clone for Limbs {
+    /// Short numbers come back inline even if they were on the heap.
+    fn clone(&self) -> Self {
+        Limbs::from(&self[..])
+    }
+}
+
+impl PartialEq for Limbs {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl fmt::Debug for Limbs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self[..], f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Limbs;
+
+    #[test]
+    fn spill_test() {
+        let mut a = Limbs::new();
+        a.push(1);
+        a.push(2);
+        assert!(matches!(a, Limbs::Inline(2, _)));
+
+        a.push(3);
+        assert!(matches!(a, Limbs::Heap(_)));
+        assert_eq!(a[..], [1, 2, 3]);
+
+        a.pop();
+        a.pop();
+        assert!(matches!(a, Limbs::Heap(_)));
+        assert_eq!(a, Limbs::from(vec![1]));
+        assert!(matches!(a.clone(), Limbs::Inline(1, _)));
+
+        a.clear();
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn resize_test() {
+        let mut a = Limbs::from(vec![7]);
+        a.resize(2, 0);
+        assert_eq!(a[..], [7, 0]);
+        a.trim();
+        assert_eq!(a[..], [7]);
+
+        a.resize(4, 9);
+        assert!(matches!(a, Limbs::Heap(_)));
+        assert_eq!(a[..], [7, 9, 9, 9]);
+
+        let mut b = Limbs::from(vec![1, 2]);
+        b.resize(1, 0);
+        b.resize(2, 5);
+        assert_eq!(b[..], [1, 5]);
+    }
+}
@@ -0,0 +1,255 @@
+//! Exponentiation, gcd and modular inverse for `BigUInt`.
+
+use super::{div_rem, BigUInt, MontgomeryContext};
+use crate::big_int::BigInt;
+
+impl BigUInt {
+    /// `self` raised to the power of `exp`, by squaring.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(3u64);
+    /// assert_eq!(a.pow(4), BigUInt::from(81u64));
+    /// ```
+    pub fn pow(&self, exp: u32) -> BigUInt {
+        self.pow_big(&BigUInt::from(exp))
+    }
+
+    /// `self` raised to the power of a big `exp`, by squaring.
+    pub fn pow_big(&self, exp: &BigUInt) -> BigUInt {
+        let mut ans = BigUInt::one();
+
+        for i in (0..exp.bit_length()).rev() {
+            ans = &ans * &ans;
+            if exp.bit(i) {
+                ans = &ans * self;
+            }
+        }
+
+        ans
+    }
+
+    /// `self` raised to the power of `exp`, modulo `modulus`.
+    /// Odd moduli go through `MontgomeryContext`.
+    /// If `modulus` is `0` it should panic as `division by zero`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(4u64);
+    /// let e = BigUInt::from(13u64);
+    /// let m = BigUInt::from(497u64);
+    ///
+    /// assert_eq!(a.modpow(&e, &m), BigUInt::from(445u64));
+    /// ```
+    pub fn modpow(&self, exp: &BigUInt, modulus: &BigUInt) -> BigUInt {
+        if let Some(ctx) = MontgomeryContext::new(modulus) {
+            return ctx.modpow(self, exp);
+        }
+
+        let base = self % modulus;
+        let mut ans = &BigUInt::one() % modulus;
+
+        for i in (0..exp.bit_length()).rev() {
+            ans = &(&ans * &ans) % modulus;
+            if exp.bit(i) {
+                ans = &(&ans * &base) % modulus;
+            }
+        }
+
+        ans
+    }
+
+    /// Greatest common divisor. `gcd(0, 0)` is `0`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(12u64);
+    /// let b = BigUInt::from(18u64);
+    ///
+    /// assert_eq!(a.gcd(&b), BigUInt::from(6u64));
+    /// ```
+    pub fn gcd(&self, other: &BigUInt) -> BigUInt {
+        let mut a = self.clone();
+        let mut b = other.clone();
+
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+
+        a
+    }
+
+    /// Least common multiple. It is `0` when either number is `0`.
+    pub fn lcm(&self, other: &BigUInt) -> BigUInt {
+        if self.is_zero() || other.is_zero() {
+            BigUInt::zero()
+        } else {
+            &(self / &self.gcd(other)) * other
+        }
+    }
+
+    /// Returns `(g, x, y)` where `g = gcd(self, other)` and `self * x + other * y = g`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let (g, x, y) = BigUInt::from(240u64).extended_gcd(&BigUInt::from(46u64));
+    ///
+    /// assert_eq!(g, BigUInt::from(2u64));
+    /// assert_eq!(x, BigInt::from(-9i64));
+    /// assert_eq!(y, BigInt::from(47i64));
+    /// ```
+    pub fn extended_gcd(&self, other: &BigUInt) -> (BigUInt, BigInt, BigInt) {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut x0, mut x1) = (BigInt::one(), BigInt::zero());
+        let (mut y0, mut y1) = (BigInt::zero(), BigInt::one());
+
+        while !r1.is_zero() {
+            let (q, r) = div_rem(&r0, &r1);
+            let q = BigInt::from(q);
+
+            (r0, r1) = (r1, r);
+            let x = &x0 - &(&q * &x1);
+            (x0, x1) = (x1, x);
+            let y = &y0 - &(&q * &y1);
+            (y0, y1) = (y1, y);
+        }
+
+        (r0, x0, y0)
+    }
+
+    /// Inverse of `self` modulo `modulus`, or `None` if they are not coprime.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(3u64);
+    ///
+    /// assert_eq!(a.mod_inverse(&BigUInt::from(11u64)), Some(BigUInt::from(4u64)));
+    /// assert_eq!(a.mod_inverse(&BigUInt::from(12u64)), None);
+    /// ```
+    pub fn mod_inverse(&self, modulus: &BigUInt) -> Option<BigUInt> {
+        if modulus.is_zero() {
+            return None;
+        }
+
+        let (g, x, _) = (self % modulus).extended_gcd(modulus);
+
+        if g == BigUInt::one() {
+            let x = x.rem_euclid(&BigInt::from(modulus.clone()));
+            BigUInt::try_from(x).ok()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::big_int::BigInt;
+    use crate::big_uint::BigUInt;
+
+    #[test]
+    fn pow_test() {
+        fn case(a: &str, e: u32, c: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            let c = BigUInt::from_str(c).unwrap();
+            assert_eq!(a.pow(e), c);
+            assert_eq!(a.pow_big(&BigUInt::from(e)), c);
+        }
+
+        case("0", 0, "1");
+        case("0", 5, "0");
+        case("7", 0, "1");
+        case("2", 100, "1267650600228229401496703205376");
+        case(
+            "12345678901234567890",
+            3,
+            "1881676372353657772490265749424677022198701224860897069000",
+        );
+    }
+
+    #[test]
+    fn modpow_test() {
+        fn case(a: &str, e: &str, m: &str, c: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            let e = BigUInt::from_str(e).unwrap();
+            let m = BigUInt::from_str(m).unwrap();
+            assert_eq!(a.modpow(&e, &m), BigUInt::from_str(c).unwrap());
+        }
+
+        case("4", "13", "497", "445");
+        case("5", "0", "1", "0");
+        case("5", "0", "7", "1");
+        case("2", "1000", "1000000007", "688423210");
+        case("3", "200", "1000000000000", "384699044001");
+        case(
+            "123456789123456789",
+            "987654321987654321",
+            "170141183460469231731687303715884105727",
+            "48162114370042460845584523944306702097",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn modpow_zero_test() {
+        BigUInt::one().modpow(&BigUInt::one(), &BigUInt::zero());
+    }
+
+    #[test]
+    fn gcd_test() {
+        fn case(a: &str, b: &str, g: &str, l: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            let b = BigUInt::from_str(b).unwrap();
+            assert_eq!(a.gcd(&b), BigUInt::from_str(g).unwrap());
+            assert_eq!(a.lcm(&b), BigUInt::from_str(l).unwrap());
+
+            let (g, x, y) = a.extended_gcd(&b);
+            let lhs = &(&BigInt::from(a) * &x) + &(&BigInt::from(b) * &y);
+            assert_eq!(lhs, BigInt::from(g));
+        }
+
+        case("0", "0", "0", "0");
+        case("0", "5", "5", "0");
+        case("12", "18", "6", "36");
+        case("240", "46", "2", "5520");
+        case(
+            "1000000000000000000000000000000",
+            "250000000000000000000000000000000005",
+            "5",
+            "50000000000000000000000000000000001000000000000000000000000000000",
+        );
+    }
+
+    #[test]
+    fn mod_inverse_test() {
+        fn case(a: &str, m: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            let m = BigUInt::from_str(m).unwrap();
+            let inv = a.mod_inverse(&m).unwrap();
+            assert!(inv < m);
+            assert_eq!(&(&a * &inv) % &m, &BigUInt::one() % &m);
+        }
+
+        case("3", "11");
+        case("10", "17");
+        case(
+            "123456789123456789",
+            "170141183460469231731687303715884105727",
+        );
+        case("5", "1");
+
+        let m = BigUInt::from(12u64);
+        assert_eq!(BigUInt::from(3u64).mod_inverse(&m), None);
+        assert_eq!(BigUInt::zero().mod_inverse(&m), None);
+        assert_eq!(BigUInt::one().mod_inverse(&BigUInt::zero()), None);
+    }
+}
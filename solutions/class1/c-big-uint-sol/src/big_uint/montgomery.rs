@@ -0,0 +1,216 @@
+//! Montgomery multiplication for repeated work modulo the same odd number.
+//!
+//! With `R = 2^(64·n)` for an `n`-limb modulus `m`, a value `x` is kept as
+//! `x·R mod m`. Multiplying two such values and reducing with REDC gives the
+//! product in the same form, using only multiplications, additions and shifts.
+
+use std::borrow::Cow;
+
+use super::{kernel, mul, BigUInt};
+
+/// Precomputed constants for Montgomery arithmetic modulo an odd number.
+///
+/// ## Example
+///
+/// ```
+/// let m = BigUInt::from(497u64);
+/// let ctx = MontgomeryContext::new(&m).unwrap();
+///
+/// let a = ctx.to_montgomery(&BigUInt::from(4u64));
+/// let b = ctx.mul(&a, &a);
+/// assert_eq!(ctx.to_plain(&b), BigUInt::from(16u64));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MontgomeryContext {
+    modulus: BigUInt,
+    /// `-m⁻¹ mod 2^64`
+    m_inv: u64,
+    /// `R² mod m`
+    r2: BigUInt,
+}
+
+impl MontgomeryContext {
+    /// Context for `modulus`, or `None` if it is even.
+    pub fn new(modulus: &BigUInt) -> Option<MontgomeryContext> {
+        let m0 = *modulus.inner.first()?;
+        if m0 & 1 == 0 {
+            return None;
+        }
+
+        // Newton's iteration doubles the correct low bits each step: 1, 2, 4, ..., 64.
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inv)));
+        }
+
+        let mut r2 = BigUInt::zero();
+        r2.set_bit(128 * modulus.inner.len());
+
+        Some(MontgomeryContext {
+            modulus: modulus.clone(),
+            m_inv: inv.wrapping_neg(),
+            r2: &r2 % modulus,
+        })
+    }
+
+    /// The modulus `m`.
+    pub fn modulus(&self) -> &BigUInt {
+        &self.modulus
+    }
+
+    /// `x`, or `x mod m` if it is not already below `m`.
+    fn reduce<'a>(&self, x: &'a BigUInt) -> Cow<'a, BigUInt> {
+        if *x < self.modulus {
+            Cow::Borrowed(x)
+        } else {
+            Cow::Owned(x % &self.modulus)
+        }
+    }
+
+    /// `t·R⁻¹ mod m` for `t < m·R`. A single subtraction is only enough in
+    /// that range, so callers pass products of values below `m`.
+    fn redc(&self, t: &[u64]) -> BigUInt {
+        let m = &self.modulus.inner;
+        let n = m.len();
+        debug_assert!(
+            t.len() <= 2 * n && kernel::cmp(&t[n.min(t.len())..], m).is_lt(),
+            "REDC input out of range"
+        );
+        let mut t = t.to_vec();
+        t.resize(2 * n + 1, 0);
+
+        for i in 0..n {
+            let u = t[i].wrapping_mul(self.m_inv);
+            let carry = kernel::mul_limb_add(&mut t[i..i + n], m, u);
+            kernel::add_limb(&mut t[i + n..], carry);
+        }
+
+        let mut t = t.split_off(n);
+        if kernel::cmp(&t, m).is_ge() {
+            kernel::sub_assign(&mut t, m);
+        }
+
+        BigUInt::from_limbs(t)
+    }
+
+    /// `x` in Montgomery form, `x·R mod m`.
+    pub fn to_montgomery(&self, x: &BigUInt) -> BigUInt {
+        let x = self.reduce(x);
+        self.redc(&mul::mul(&x.inner, &self.r2.inner))
+    }
+
+    /// `x` back from Montgomery form, `x·R⁻¹ mod m`.
+    ///
+    /// Values in Montgomery form are below `m` when they come from this
+    /// context; others are reduced modulo `m` first.
+    pub fn to_plain(&self, x: &BigUInt) -> BigUInt {
+        self.redc(&self.reduce(x).inner)
+    }
+
+    /// Product of two values in Montgomery form. Like `to_plain`, inputs
+    /// not below `m` are reduced first.
+    pub fn mul(&self, a: &BigUInt, b: &BigUInt) -> BigUInt {
+        let (a, b) = (self.reduce(a), self.reduce(b));
+        self.redc(&mul::mul(&a.inner, &b.inner))
+    }
+
+    /// Square of a value in Montgomery form, reduced first if not below `m`.
+    pub fn square(&self, a: &BigUInt) -> BigUInt {
+        self.mul(a, a)
+    }
+
+    /// `base` raised to the power of `exp` modulo `m`. Input and output are plain values.
+    pub fn modpow(&self, base: &BigUInt, exp: &BigUInt) -> BigUInt {
+        let x = self.to_montgomery(base);
+        let mut ans = self.to_montgomery(&BigUInt::one());
+
+        for i in (0..exp.bit_length()).rev() {
+            ans = self.square(&ans);
+            if exp.bit(i) {
+                ans = self.mul(&ans, &x);
+            }
+        }
+
+        self.to_plain(&ans)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::MontgomeryContext;
+    use crate::big_uint::BigUInt;
+
+    #[test]
+    fn new_test() {
+        assert!(MontgomeryContext::new(&BigUInt::zero()).is_none());
+        assert!(MontgomeryContext::new(&BigUInt::from(10u64)).is_none());
+        assert!(MontgomeryContext::new(&BigUInt::one()).is_some());
+
+        let m = BigUInt::from(497u64);
+        assert_eq!(MontgomeryContext::new(&m).unwrap().modulus(), &m);
+    }
+
+    #[test]
+    fn mul_test() {
+        fn case(a: &str, b: &str, m: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            let b = BigUInt::from_str(b).unwrap();
+            let m = BigUInt::from_str(m).unwrap();
+            let ctx = MontgomeryContext::new(&m).unwrap();
+
+            let x = ctx.to_montgomery(&a);
+            let y = ctx.to_montgomery(&b);
+            assert_eq!(ctx.to_plain(&x), &a % &m);
+            assert_eq!(ctx.to_plain(&ctx.mul(&x, &y)), &(&a * &b) % &m);
+            assert_eq!(ctx.to_plain(&ctx.square(&x)), &(&a * &a) % &m);
+        }
+
+        case("4", "5", "7");
+        case("0", "5", "7");
+        case("6", "6", "1");
+        case(
+            "18446744073709551615",
+            "18446744073709551614",
+            "18446744073709551557",
+        );
+        case(
+            "123456789123456789123456789123456789",
+            "987654321987654321987654321987654321987654321",
+            "170141183460469231731687303715884105727",
+        );
+        case(
+            "340282366920938463463374607431768211455",
+            "340282366920938463463374607431768211454",
+            "340282366920938463463374607431768211297",
+        );
+    }
+
+    #[test]
+    fn unreduced_test() {
+        let m = BigUInt::from(497u64);
+        let ctx = MontgomeryContext::new(&m).unwrap();
+        let x = ctx.to_montgomery(&BigUInt::from(123u64));
+        let y = ctx.to_montgomery(&BigUInt::from(456u64));
+
+        // The same residues, far above the modulus.
+        let big = &m << 200;
+        let (x2, y2) = (&x + &big, &y + &(&big * &m));
+        assert_eq!(ctx.mul(&x2, &y2), ctx.mul(&x, &y));
+        assert_eq!(ctx.square(&x2), ctx.square(&x));
+        assert_eq!(ctx.to_plain(&x2), BigUInt::from(123u64));
+    }
+
+    #[test]
+    fn modpow_test() {
+        let m = BigUInt::from_str("1000000000000000000000000000000000000000000000000000000007")
+            .unwrap();
+        let ctx = MontgomeryContext::new(&m).unwrap();
+        let a = BigUInt::from_str("31415926535897932384626433832795028841971").unwrap();
+
+        for e in [0u32, 1, 2, 3, 17, 64, 100] {
+            assert_eq!(ctx.modpow(&a, &BigUInt::from(e)), &a.pow(e) % &m, "{e}");
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Multiplication kernels.
+//!
+//! `mul` picks the algorithm by the size of the smaller operand:
+//! schoolbook below `KARATSUBA_THRESHOLD` limbs, Karatsuba below
+//! `TOOM3_THRESHOLD` limbs, Toom-3 below `NTT_THRESHOLD` limbs and the
+//! number-theoretic transform in `ntt` above that.
+
+use std::thread;
+
+use super::{kernel, ntt, par};
+
+/// Smallest operand length, in limbs, that is multiplied with Karatsuba.
+pub(crate) const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Smallest operand length, in limbs, that is multiplied with Toom-3.
+pub(crate) const TOOM3_THRESHOLD: usize = 128;
+
+/// Smallest operand length, in limbs, that is multiplied with the NTT.
+pub(crate) const NTT_THRESHOLD: usize = 16384;
+
+/// Returns the normalized product of `a` and `b`.
+pub(crate) fn mul(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let a = &a[..kernel::trimmed_len(a)];
+    let b = &b[..kernel::trimmed_len(b)];
+
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut out = vec![0; a.len() + b.len()];
+    mul_into(&mut out, a, b);
+    kernel::trim(&mut out);
+    out
+}
+
+/// `out = a * b`, where `out` is zeroed and `out.len() == a.len() + b.len()`.
+fn mul_into(out: &mut [u64], a: &[u64], b: &[u64]) {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    if b.len() < KARATSUBA_THRESHOLD {
+        schoolbook(out, a, b);
+    } else if 2 * b.len() <= a.len() {
+        unbalanced(out, a, b);
+    } else if b.len() < TOOM3_THRESHOLD {
+        karatsuba(out, a, b);
+    } else if b.len() < NTT_THRESHOLD {
+        toom3(out, a, b);
+    } else {
+        ntt::mul_into(out, a, b);
+    }
+}
+
+/// In-place O(n·m) multiplication; every row is accumulated straight into `out`.
+pub(crate) fn schoolbook(out: &mut [u64], a: &[u64], b: &[u64]) {
+    for (i, &y) in b.iter().enumerate() {
+        out[i + a.len()] = kernel::mul_limb_add(&mut out[i..i + a.len()], a, y);
+    }
+}
+
+/// Splits the longer operand into pieces as long as the shorter one,
+/// so that each piece is a balanced multiplication.
+fn unbalanced(out: &mut [u64], a: &[u64], b: &[u64]) {
+    let mut t = vec![0; 2 * b.len()];
+
+    for (i, chunk) in a.chunks(b.len()).enumerate() {
+        let t = &mut t[..chunk.len() + b.len()];
+        t.fill(0);
+        mul_into(t, chunk, b);
+        kernel::add_assign(&mut out[i * b.len()..], t);
+    }
+}
+
+/// `a + b`, not normalized.
+fn add(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = a.to_vec();
+
+    if kernel::add_assign(&mut out, b) {
+        out.push(1);
+    }
+
+    out
+}
+
+/// Karatsuba: `a * b = z2·B²ᵐ + z1·Bᵐ + z0` with three half-size products,
+/// where `z1 = (a0 + a1)(b0 + b1) - z0 - z2`.
+fn karatsuba(out: &mut [u64], a: &[u64], b: &[u64]) {
+    let m = a.len().div_ceil(2);
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m.min(b.len()));
+
+    let z0 = mul(a0, b0);
+    let z2 = mul(a1, b1);
+    let z1 = mul(&add(a0, a1), &add(b0, b1));
+    karatsuba_join(out, m, z0, z1, z2);
+}
+
+/// Writes `z2·B²ᵐ + (z1 - z0 - z2)·Bᵐ + z0` to `out`, where `z1` is the
+/// product of the sums.
+fn karatsuba_join(out: &mut [u64], m: usize, z0: Vec<u64>, mut z1: Vec<u64>, z2: Vec<u64>) {
+    kernel::sub_assign(&mut z1, &z0);
+    kernel::sub_assign(&mut z1, &z2);
+    kernel::trim(&mut z1);
+
+    out[..z0.len()].copy_from_slice(&z0);
+    kernel::add_assign(&mut out[m..], &z1);
+    kernel::add_assign(&mut out[2 * m..], &z2);
+}
+
+/// `mul` with the top levels spread over up to `threads` threads.
+///
+/// Unbalanced operands hand out their pieces to the threads. Balanced ones
+/// take one Karatsuba step and compute the three products concurrently,
+/// each of them splitting further with its share of the threads. The
+/// shares add up to `threads`, counting the calling thread, so no more than
+/// `threads` threads are ever busy.
+pub(crate) fn par_mul(a: &[u64], b: &[u64], threads: usize) -> Vec<u64> {
+    let a = &a[..kernel::trimmed_len(a)];
+    let b = &b[..kernel::trimmed_len(b)];
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    if threads <= 1 || b.len() < par::PAR_THRESHOLD {
+        return mul(a, b);
+    }
+
+    let mut out = vec![0; a.len() + b.len()];
+    if 2 * b.len() <= a.len() {
+        let pieces = a.chunks(b.len()).collect::<Vec<_>>();
+        let products = thread::scope(|s| {
+            let handles = pieces
+                .chunks(pieces.len().div_ceil(threads))
+                .map(|group| s.spawn(move || group.iter().map(|x| mul(x, b)).collect::<Vec<_>>()))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        for (i, p) in products.iter().enumerate() {
+            kernel::add_assign(&mut out[i * b.len()..], p);
+        }
+    } else {
+        let m = a.len().div_ceil(2);
+        let (a0, a1) = a.split_at(m);
+        let (b0, b1) = b.split_at(m.min(b.len()));
+        let (s0, s1) = (add(a0, a1), add(b0, b1));
+        // With three or more threads, `z0` and `z2` get `t` each and `z1` the
+        // rest, this thread being one of the rest.
+        let t = threads / 3;
+
+        let (z0, z1, z2) = thread::scope(|s| {
+            if threads < 3 {
+                // One helper takes `z0` and this thread computes the others.
+                let z0 = s.spawn(|| mul(a0, b0));
+                let z2 = mul(a1, b1);
+                let z1 = mul(&s0, &s1);
+                (z0.join().unwrap(), z1, z2)
+            } else {
+                let z0 = s.spawn(|| par_mul(a0, b0, t));
+                let z2 = s.spawn(|| par_mul(a1, b1, t));
+                let z1 = par_mul(&s0, &s1, threads - 2 * t);
+                (z0.join().unwrap(), z1, z2.join().unwrap())
+            }
+        });
+        karatsuba_join(&mut out, m, z0, z1, z2);
+    }
+
+    kernel::trim(&mut out);
+    out
+}
+
+/// Signed value used by the Toom-3 evaluation and interpolation steps.
+#[derive(Clone)]
+struct Signed {
+    neg: bool,
+    mag: Vec<u64>,
+}
+
+impl Signed {
+    fn new(neg: bool, mut mag: Vec<u64>) -> Signed {
+        kernel::trim(&mut mag);
+        Signed {
+            neg: neg && !mag.is_empty(),
+            mag,
+        }
+    }
+
+    fn from_slice(x: &[u64]) -> Signed {
+        Signed::new(false, x.to_vec())
+    }
+
+    fn add(&self, rhs: &Signed) -> Signed {
+        if self.neg == rhs.neg {
+            Signed::new(self.neg, add(&self.mag, &rhs.mag))
+        } else if kernel::cmp(&self.mag, &rhs.mag).is_ge() {
+            let mut mag = self.mag.clone();
+            kernel::sub_assign(&mut mag, &rhs.mag);
+            Signed::new(self.neg, mag)
+        } else {
+            let mut mag = rhs.mag.clone();
+            kernel::sub_assign(&mut mag, &self.mag);
+            Signed::new(rhs.neg, mag)
+        }
+    }
+
+    fn sub(&self, rhs: &Signed) -> Signed {
+        self.add(&Signed {
+            neg: !rhs.neg && !rhs.mag.is_empty(),
+            mag: rhs.mag.clone(),
+        })
+    }
+
+    fn mul(&self, rhs: &Signed) -> Signed {
+        Signed::new(self.neg != rhs.neg, mul(&self.mag, &rhs.mag))
+    }
+
+    fn mul_limb(&self, m: u64) -> Signed {
+        let mut mag = self.mag.clone();
+        let carry = kernel::mul_limb(&mut mag, m);
+        mag.push(carry);
+        Signed::new(self.neg, mag)
+    }
+
+    /// Division that is known to leave no remainder.
+    fn div_exact(&self, d: u64) -> Signed {
+        let mut mag = self.mag.clone();
+        let rem = kernel::div_rem_limb(&mut mag, d);
+        debug_assert_eq!(rem, 0);
+        Signed::new(self.neg, mag)
+    }
+}
+
+/// Toom-3: split both operands into three parts, evaluate at `0, 1, -1, -2, ∞`,
+/// multiply pointwise and interpolate (Bodrato's sequence).
+fn toom3(out: &mut [u64], a: &[u64], b: &[u64]) {
+    let k = a.len().div_ceil(3);
+
+    let split = |x: &[u64]| {
+        let i = k.min(x.len());
+        let j = (2 * k).min(x.len());
+        [&x[..i], &x[i..j], &x[j..]].map(Signed::from_slice)
+    };
+
+    let eval = |[x0, x1, x2]: [Signed; 3]| {
+        let t = x0.add(&x2);
+        let p1 = t.add(&x1);
+        let pm1 = t.sub(&x1);
+        let pm2 = pm1.add(&x2).mul_limb(2).sub(&x0);
+        [x0, p1, pm1, pm2, x2]
+    };
+
+    let [p0, p1, pm1, pm2, pinf] = eval(split(a));
+    let [q0, q1, qm1, qm2, qinf] = eval(split(b));
+
+    let w0 = p0.mul(&q0);
+    let w1 = p1.mul(&q1);
+    let wm1 = pm1.mul(&qm1);
+    let wm2 = pm2.mul(&qm2);
+    let winf = pinf.mul(&qinf);
+
+    let r3 = wm2.sub(&w1).div_exact(3);
+    let r1 = w1.sub(&wm1).div_exact(2);
+    let r2 = wm1.sub(&w0);
+    let r3 = r2.sub(&r3).div_exact(2).add(&winf.mul_limb(2));
+    let r2 = r2.add(&r1).sub(&winf);
+    let r1 = r1.sub(&r3);
+
+    for (i, r) in [w0, r1, r2, r3, winf].iter().enumerate() {
+        debug_assert!(!r.neg);
+        if !r.mag.is_empty() {
+            kernel::add_assign(&mut out[i * k..], &r.mag);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{mul, par_mul, schoolbook, KARATSUBA_THRESHOLD, NTT_THRESHOLD, TOOM3_THRESHOLD};
+    use crate::big_uint::par::PAR_THRESHOLD;
+
+    fn limbs(seed: &mut u64, n: usize) -> Vec<u64> {
+        (0..n)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 7;
+                *seed ^= *seed << 17;
+                *seed
+            })
+            .collect()
+    }
+
+    fn reference(a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut out = vec![0; a.len() + b.len()];
+        schoolbook(&mut out, a, b);
+        while let Some(&0) = out.last() {
+            out.pop();
+        }
+        out
+    }
+
+    #[test]
+    fn mul_matches_schoolbook_test() {
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+        let sizes = [
+            (1, 1),
+            (KARATSUBA_THRESHOLD - 1, KARATSUBA_THRESHOLD + 1),
+            (KARATSUBA_THRESHOLD, KARATSUBA_THRESHOLD),
+            (KARATSUBA_THRESHOLD + 7, KARATSUBA_THRESHOLD + 3),
+            (100, 37),
+            (TOOM3_THRESHOLD - 1, TOOM3_THRESHOLD - 1),
+            (TOOM3_THRESHOLD, TOOM3_THRESHOLD),
+            (TOOM3_THRESHOLD * 2 + 1, TOOM3_THRESHOLD + 5),
+            (500, 499),
+            (1000, 10),
+            (NTT_THRESHOLD + 11, NTT_THRESHOLD + 3),
+        ];
+
+        for (n, m) in sizes {
+            let a = limbs(&mut seed, n);
+            let b = limbs(&mut seed, m);
+            assert_eq!(mul(&a, &b), reference(&a, &b), "{n} x {m}");
+        }
+    }
+
+    #[test]
+    fn mul_carry_test() {
+        for n in [
+            1,
+            KARATSUBA_THRESHOLD,
+            TOOM3_THRESHOLD,
+            3 * TOOM3_THRESHOLD + 2,
+        ] {
+            let a = vec![u64::MAX; n];
+            let b = vec![u64::MAX; n - n / 3];
+            assert_eq!(mul(&a, &b), reference(&a, &b), "{n}");
+        }
+    }
+
+    #[test]
+    fn par_mul_threads_test() {
+        let mut seed = 0x9e37_79b9_7f4a_7c15;
+        let a = limbs(&mut seed, 4 * PAR_THRESHOLD + 3);
+        let b = limbs(&mut seed, 4 * PAR_THRESHOLD - 5);
+
+        for threads in [2, 3, 4, 5, 9] {
+            assert_eq!(par_mul(&a, &b, threads), mul(&a, &b), "{threads}");
+        }
+    }
+
+    #[test]
+    fn mul_zero_test() {
+        assert_eq!(mul(&[], &[1, 2, 3]), Vec::<u64>::new());
+        assert_eq!(mul(&[0, 0], &[1, 2, 3]), Vec::<u64>::new());
+    }
+}
@@ -0,0 +1,303 @@
+//! Multiplication by number-theoretic transform over three primes.
+//!
+//! The limbs of each operand are used directly as coefficients. Their
+//! cyclic convolution is computed modulo three primes `c·2^k + 1` just below
+//! `2^62`, and every coefficient is rebuilt from its residues with Garner's
+//! form of the Chinese remainder theorem. A coefficient is a sum of at most
+//! `min(a.len(), b.len())` products below `2^128`, and the three primes
+//! multiply to about `2^183`, so the result is exact for any realistic size.
+
+use super::kernel;
+
+/// Arithmetic modulo an odd prime `p < 2^62`, in Montgomery form with `R = 2^64`.
+struct Field {
+    p: u64,
+    /// `-p⁻¹ mod 2^64`
+    p_inv: u64,
+    /// `R² mod p`
+    r2: u64,
+    /// A primitive root modulo `p`.
+    g: u64,
+}
+
+impl Field {
+    const fn new(p: u64, g: u64) -> Field {
+        // Newton's iteration doubles the correct low bits each step.
+        let mut inv = 1u64;
+        let mut i = 0;
+        while i < 6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inv)));
+            i += 1;
+        }
+
+        let r = ((1u128 << 64) % p as u128) as u64;
+        Field {
+            p,
+            p_inv: inv.wrapping_neg(),
+            r2: (r as u128 * r as u128 % p as u128) as u64,
+            g,
+        }
+    }
+
+    /// `t·R⁻¹ mod p` for `t < p·R`.
+    fn redc(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.p_inv);
+        let u = ((t + m as u128 * self.p as u128) >> 64) as u64;
+        if u >= self.p {
+            u - self.p
+        } else {
+            u
+        }
+    }
+
+    /// `a·b·R⁻¹ mod p`: the product of two Montgomery values, or the plain
+    /// product if exactly one of them is in Montgomery form.
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        self.redc(a as u128 * b as u128)
+    }
+
+    fn add(&self, a: u64, b: u64) -> u64 {
+        let s = a + b;
+        if s >= self.p {
+            s - self.p
+        } else {
+            s
+        }
+    }
+
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b {
+            a - b
+        } else {
+            a + self.p - b
+        }
+    }
+
+    fn to_mont(&self, x: u64) -> u64 {
+        self.mul(x % self.p, self.r2)
+    }
+
+    fn pow(&self, mut x: u64, mut e: u64) -> u64 {
+        let mut acc = self.to_mont(1);
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = self.mul(acc, x);
+            }
+            x = self.mul(x, x);
+            e >>= 1;
+        }
+        acc
+    }
+
+    /// `x⁻¹` for a Montgomery value `x`, also in Montgomery form.
+    fn inv(&self, x: u64) -> u64 {
+        self.pow(x, self.p - 2)
+    }
+
+    /// Root table for a transform of length `n`: `out[h + j] = w_h^j` for
+    /// `j < h`, where `w_h` is a primitive `2h`-th root of unity (or its
+    /// inverse), so every butterfly stage reads its roots contiguously.
+    fn twiddles(&self, n: usize, inverse: bool) -> Vec<u64> {
+        let mut w = self.pow(self.to_mont(self.g), (self.p - 1) / n as u64);
+        if inverse {
+            w = self.inv(w);
+        }
+
+        let mut out = vec![0; n.max(2)];
+        let mut x = self.to_mont(1);
+        for t in &mut out[n / 2..n] {
+            *t = x;
+            x = self.mul(x, w);
+        }
+        for i in (1..n / 2).rev() {
+            out[i] = out[2 * i];
+        }
+        out
+    }
+
+    /// Decimation-in-frequency transform; the output is in bit-reversed order.
+    fn forward(&self, a: &mut [u64], tw: &[u64]) {
+        let mut half = a.len() / 2;
+        while half > 0 {
+            let tw = &tw[half..2 * half];
+            for block in a.chunks_exact_mut(2 * half) {
+                let (lo, hi) = block.split_at_mut(half);
+                for ((x, y), &w) in lo.iter_mut().zip(hi).zip(tw) {
+                    let (u, v) = (*x, *y);
+                    *x = self.add(u, v);
+                    *y = self.mul(self.sub(u, v), w);
+                }
+            }
+            half /= 2;
+        }
+    }
+
+    /// Decimation-in-time transform from bit-reversed order back to natural
+    /// order; with inverse twiddles it undoes `forward` up to a factor of `n`.
+    fn backward(&self, a: &mut [u64], tw: &[u64]) {
+        let mut half = 1;
+        while half < a.len() {
+            let tw = &tw[half..2 * half];
+            for block in a.chunks_exact_mut(2 * half) {
+                let (lo, hi) = block.split_at_mut(half);
+                for ((x, y), &w) in lo.iter_mut().zip(hi).zip(tw) {
+                    let u = *x;
+                    let v = self.mul(*y, w);
+                    *x = self.add(u, v);
+                    *y = self.sub(u, v);
+                }
+            }
+            half *= 2;
+        }
+    }
+
+    /// The first `a.len() + b.len() - 1` coefficients of `a * b` modulo `p`,
+    /// in plain form, using a transform of length `n`.
+    fn convolve(&self, a: &[u64], b: &[u64], n: usize) -> Vec<u64> {
+        let load = |x: &[u64]| {
+            let mut v = vec![0; n];
+            for (d, &s) in v.iter_mut().zip(x) {
+                *d = self.to_mont(s);
+            }
+            v
+        };
+
+        let tw = self.twiddles(n, false);
+        let mut fa = load(a);
+        let mut fb = load(b);
+        self.forward(&mut fa, &tw);
+        self.forward(&mut fb, &tw);
+
+        for (x, &y) in fa.iter_mut().zip(&fb) {
+            *x = self.mul(*x, y);
+        }
+
+        self.backward(&mut fa, &self.twiddles(n, true));
+
+        // Multiplying by the plain `1/n` also takes the values out of
+        // Montgomery form.
+        let n_inv = self.redc(self.inv(self.to_mont(n as u64)) as u128);
+        fa.truncate(a.len() + b.len() - 1);
+        for x in &mut fa {
+            *x = self.mul(*x, n_inv);
+        }
+        fa
+    }
+}
+
+const P1: Field = Field::new(29 << 57 | 1, 3);
+const P2: Field = Field::new(69 << 55 | 1, 5);
+const P3: Field = Field::new(57 << 55 | 1, 7);
+
+/// `out = a * b`, where `out` is zeroed and `out.len() == a.len() + b.len()`.
+pub(crate) fn mul_into(out: &mut [u64], a: &[u64], b: &[u64]) {
+    if a.is_empty() || b.is_empty() {
+        return;
+    }
+
+    let n = (a.len() + b.len() - 1).next_power_of_two();
+    let r1 = P1.convolve(a, b, n);
+    let r2 = P2.convolve(a, b, n);
+    let r3 = P3.convolve(a, b, n);
+
+    // Garner: x = x1 + p1·t2 + p1·p2·t3 with t2 < p2 and t3 < p3. The
+    // constants are in Montgomery form, so `mul` with a plain value
+    // gives a plain result.
+    let p1_inv = P2.inv(P2.to_mont(P1.p));
+    let p12 = P1.p as u128 * P2.p as u128;
+    let p12_inv = P3.inv(P3.to_mont((p12 % P3.p as u128) as u64));
+    let p1_mod_p3 = P3.to_mont(P1.p);
+
+    // The running sum never needs more than three limbs: after the low limb
+    // is emitted it is below 2^128, and each coefficient is below 2^184.
+    let mut acc = [0u64; 3];
+    for (i, ((&x1, &x2), &x3)) in r1.iter().zip(&r2).zip(&r3).enumerate() {
+        let t2 = P2.mul(P2.sub(x2, x1 % P2.p), p1_inv);
+        let u = P3.add(x1 % P3.p, P3.mul(t2 % P3.p, p1_mod_p3));
+        let t3 = P3.mul(P3.sub(x3, u), p12_inv);
+
+        let low = x1 as u128 + P1.p as u128 * t2 as u128;
+        let mid = (p12 as u64) as u128 * t3 as u128;
+        let high = (p12 >> 64) * t3 as u128;
+
+        kernel::add_assign(&mut acc, &[low as u64, (low >> 64) as u64]);
+        kernel::add_assign(&mut acc, &[mid as u64, (mid >> 64) as u64]);
+        kernel::add_assign(&mut acc[1..], &[high as u64, (high >> 64) as u64]);
+
+        out[i] = acc[0];
+        acc = [acc[1], acc[2], 0];
+    }
+
+    let len = r1.len();
+    out[len] = acc[0];
+    debug_assert!(acc[1] == 0 && acc[2] == 0);
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::mul::schoolbook;
+    use super::{mul_into, P1, P2, P3};
+
+    fn limbs(seed: &mut u64, n: usize) -> Vec<u64> {
+        (0..n)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 7;
+                *seed ^= *seed << 17;
+                *seed
+            })
+            .collect()
+    }
+
+    fn case(a: &[u64], b: &[u64]) {
+        let mut expected = vec![0; a.len() + b.len()];
+        schoolbook(&mut expected, a, b);
+
+        let mut out = vec![0; a.len() + b.len()];
+        mul_into(&mut out, a, b);
+        assert_eq!(out, expected, "{} x {}", a.len(), b.len());
+    }
+
+    #[test]
+    fn field_test() {
+        for f in [P1, P2, P3] {
+            assert!(f.p < 1 << 62);
+            assert_eq!(f.p.wrapping_mul(f.p_inv.wrapping_neg()), 1);
+
+            let x = f.to_mont(123456789);
+            assert_eq!(f.redc(f.mul(x, f.inv(x)) as u128), 1);
+            // g^((p-1)/2) = -1, so the root has full order on the 2-part.
+            let g = f.to_mont(f.g);
+            assert_eq!(f.redc(f.pow(g, (f.p - 1) / 2) as u128), f.p - 1);
+        }
+    }
+
+    #[test]
+    fn ntt_matches_schoolbook_test() {
+        let mut seed = 0x9e37_79b9_7f4a_7c15;
+        for (n, m) in [
+            (1, 1),
+            (2, 1),
+            (3, 3),
+            (17, 5),
+            (64, 64),
+            (100, 37),
+            (255, 257),
+            (1000, 999),
+        ] {
+            let a = limbs(&mut seed, n);
+            let b = limbs(&mut seed, m);
+            case(&a, &b);
+            case(&b, &a);
+        }
+    }
+
+    #[test]
+    fn ntt_carry_test() {
+        for n in [1, 2, 31, 512, 777] {
+            let a = vec![u64::MAX; n];
+            let b = vec![u64::MAX; n + 3];
+            case(&a, &b);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Opt-in multi-threaded variants of the divide-and-conquer operations.
+//!
+//! The work is split at the top levels of the recursion and run on
+//! `std::thread::scope` workers, so no thread pool is kept around. Each
+//! variant gives exactly the same result as its sequential counterpart;
+//! only operands of at least `PAR_THRESHOLD` limbs are worth a thread.
+
+use super::{mul, threads, BigUInt};
+
+/// Smallest operand length, in limbs, whose work is split across threads.
+pub(crate) const PAR_THRESHOLD: usize = 1024;
+
+impl BigUInt {
+    /// `self * rhs`, computed on up to `threads()` threads.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = &BigUInt::one() << 100_000;
+    /// assert_eq!(a.par_mul(&a), &a * &a);
+    /// ```
+    pub fn par_mul(&self, rhs: &BigUInt) -> BigUInt {
+        BigUInt::from_limbs(mul::par_mul(&self.inner, &rhs.inner, threads()))
+    }
+
+    /// Decimal digits of the number, like `to_string`, computed on up to
+    /// `threads()` threads.
+    pub fn par_to_string(&self) -> String {
+        self.to_str_radix_with(10, threads())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::big_uint::{set_threads, threads, BigUInt, XorShift64};
+
+    use super::PAR_THRESHOLD;
+
+    #[test]
+    fn threads_test() {
+        assert!(threads() >= 1);
+    }
+
+    #[test]
+    fn par_mul_test() {
+        set_threads(4);
+        let mut rng = XorShift64::new(23);
+
+        for (x, y) in [
+            (0, 5000),
+            (100, 100),
+            (PAR_THRESHOLD, PAR_THRESHOLD),
+            (3 * PAR_THRESHOLD + 5, PAR_THRESHOLD + 1),
+            (2 * PAR_THRESHOLD, 2 * PAR_THRESHOLD - 3),
+            (9 * PAR_THRESHOLD, PAR_THRESHOLD),
+        ] {
+            let a = BigUInt::random_bits(64 * x, &mut rng);
+            let b = BigUInt::random_bits(64 * y, &mut rng);
+            assert_eq!(a.par_mul(&b), &a * &b, "{x} x {y}");
+            assert_eq!(b.par_mul(&a), &a * &b, "{y} x {x}");
+        }
+
+        let a = BigUInt::from_limbs(vec![u64::MAX; 3 * PAR_THRESHOLD]);
+        assert_eq!(a.par_mul(&a), &a * &a);
+    }
+
+    #[test]
+    fn par_to_string_test() {
+        set_threads(3);
+        let mut rng = XorShift64::new(29);
+
+        for bits in [0, 64, 64 * PAR_THRESHOLD, 64 * 4 * PAR_THRESHOLD + 17] {
+            let a = BigUInt::random_bits(bits, &mut rng);
+            assert_eq!(a.par_to_string(), a.to_string(), "{bits}");
+        }
+
+        let a = &BigUInt::from(10u64).pow(100_000) - &BigUInt::one();
+        assert_eq!(a.par_to_string(), "9".repeat(100_000));
+    }
+}
@@ -0,0 +1,313 @@
+//! Reading numbers with configurable leniency.
+//!
+//! `ParseOptions` starts from the rules of `BigUInt::from_str` and each
+//! setter relaxes or tightens one of them. Errors carry the byte offset in
+//! the original input, including any whitespace or sign that was skipped.
+
+use super::{radix, BigUInt, ParseBigUIntError, ParseBigUIntErrorKind as Kind};
+
+/// Rules for reading a `BigUInt` from a string.
+///
+/// ## Examples
+///
+/// ```
+/// let opts = ParseOptions::new().leading_zeros(true).sign(true).trim_whitespace(true);
+/// assert_eq!(opts.parse(" +007 ").unwrap(), BigUInt::from(7u64));
+///
+/// let strict = ParseOptions::new().separators(&[',']).strict_grouping(true);
+/// assert!(strict.parse("1,000,000").is_ok());
+/// assert_eq!(strict.parse("10,00").unwrap_err().offset(), 2);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseOptions {
+    leading_zeros: bool,
+    sign: bool,
+    trim: bool,
+    separators: Vec<char>,
+    strict_grouping: bool,
+    prefixes: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            leading_zeros: false,
+            sign: false,
+            trim: false,
+            separators: vec![',', '_'],
+            strict_grouping: false,
+            prefixes: true,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// The rules of `BigUInt::from_str`.
+    pub fn new() -> ParseOptions {
+        ParseOptions::default()
+    }
+
+    /// Whether decimal numbers may start with `0`, like `007`. Numbers with
+    /// a radix prefix always may.
+    pub fn leading_zeros(mut self, allow: bool) -> Self {
+        self.leading_zeros = allow;
+        self
+    }
+
+    /// Whether a `+` may come before the number.
+    pub fn sign(mut self, allow: bool) -> Self {
+        self.sign = allow;
+        self
+    }
+
+    /// Whether whitespace around the number is ignored.
+    pub fn trim_whitespace(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Characters that may appear between digits; `,` and `_` by default.
+    pub fn separators(mut self, separators: &[char]) -> Self {
+        self.separators = separators.to_vec();
+        self
+    }
+
+    /// Whether separators, if any, must split the digits into groups of
+    /// three from the right, like `1,000,000`.
+    pub fn strict_grouping(mut self, strict: bool) -> Self {
+        self.strict_grouping = strict;
+        self
+    }
+
+    /// Whether a `0x`, `0o` or `0b` prefix reads the rest as hex, octal or
+    /// binary. Without it, only decimal digits are accepted.
+    pub fn radix_prefix(mut self, allow: bool) -> Self {
+        self.prefixes = allow;
+        self
+    }
+
+    /// Reads `s` with these rules.
+    pub fn parse(&self, s: &str) -> Result<BigUInt, ParseBigUIntError> {
+        let mut s = s;
+        let mut offset = 0;
+
+        if self.trim {
+            let t = s.trim_start();
+            offset += s.len() - t.len();
+            s = t.trim_end();
+        }
+        if let Some(t) = s.strip_prefix('+').filter(|_| self.sign) {
+            offset += 1;
+            s = t;
+        }
+
+        let (radix, s, offset, zeros) = match radix::strip_prefix(s).filter(|_| self.prefixes) {
+            Some((radix, t)) => (radix, t, offset + 2, true),
+            None => (10, s, offset, self.leading_zeros),
+        };
+        let v = self.digits(s, radix, offset, zeros)?;
+        Ok(radix::from_digits(&v, radix))
+    }
+
+    /// Digits of `s` in base `radix`, without separators. `offset` is the
+    /// position of `s` in the input, for errors.
+    pub(super) fn digits(
+        &self,
+        s: &str,
+        radix: u32,
+        offset: usize,
+        leading_zeros: bool,
+    ) -> Result<Vec<u32>, ParseBigUIntError> {
+        let err = |kind, i: usize| ParseBigUIntError::new(kind, offset + i, s[i..].chars().next());
+
+        match s.chars().next() {
+            None => return Err(err(Kind::Empty, 0)),
+            Some(c) if !c.is_digit(radix) => return Err(err(Kind::NotStartingWithDigit, 0)),
+            Some('0') if !leading_zeros && s.len() > 1 => return Err(err(Kind::LeadingZero, 0)),
+            _ => {}
+        }
+
+        let mut v = Vec::with_capacity(s.len());
+        // Number of digits before each separator, and its byte position.
+        let mut seps = Vec::new();
+
+        for (i, c) in s.char_indices() {
+            if let Some(d) = c.to_digit(radix) {
+                v.push(d);
+            } else if self.separators.contains(&c) {
+                seps.push((v.len(), i));
+            } else {
+                return Err(err(Kind::InvalidCharacter, i));
+            }
+        }
+
+        if self.strict_grouping && !seps.is_empty() {
+            let n = v.len();
+            let mut seps = seps.into_iter().peekable();
+            // A separator is due after every digit that leaves a multiple of
+            // three digits to its right.
+            let mut k = (n - 1) % 3 + 1;
+
+            while k < n {
+                match seps.peek() {
+                    Some(&(at, _)) if at == k => {
+                        seps.next();
+                    }
+                    Some(&(at, i)) if at < k || (n - at) % 3 != 0 => {
+                        return Err(err(Kind::BadGrouping, i))
+                    }
+                    _ => {
+                        let (i, _) = s
+                            .char_indices()
+                            .filter(|(_, c)| c.is_digit(radix))
+                            .nth(k)
+                            .unwrap();
+                        return Err(err(Kind::BadGrouping, i));
+                    }
+                }
+                k += 3;
+            }
+
+            if let Some((_, i)) = seps.next() {
+                return Err(err(Kind::BadGrouping, i));
+            }
+        }
+
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::big_uint::{BigUInt, ParseBigUIntErrorKind as Kind};
+
+    use super::ParseOptions;
+
+    #[test]
+    fn options_test() {
+        fn case(opts: &ParseOptions, s: &str, c: Option<u64>) {
+            assert_eq!(opts.parse(s).ok(), c.map(BigUInt::from), "{s:?}");
+        }
+
+        let default = ParseOptions::new();
+        for s in ["007", "+5", " 42 "] {
+            case(&default, s, None);
+        }
+        case(&default, "1,,,_2", Some(12));
+        case(&default, "0", Some(0));
+        case(&default, "0x00ff", Some(255));
+
+        let lenient = ParseOptions::new()
+            .leading_zeros(true)
+            .sign(true)
+            .trim_whitespace(true);
+        case(&lenient, "007", Some(7));
+        case(&lenient, "+5", Some(5));
+        case(&lenient, "\t 42 \n", Some(42));
+        case(&lenient, " +0b101 ", Some(5));
+        case(&lenient, "++5", None);
+        case(&lenient, "+ 5", None);
+        case(&lenient, "-5", None);
+
+        let no_seps = ParseOptions::new().separators(&[]);
+        case(&no_seps, "1000", Some(1000));
+        case(&no_seps, "1_000", None);
+
+        let decimal = ParseOptions::new().radix_prefix(false);
+        case(&decimal, "1_000", Some(1000));
+        case(&decimal, "0x10", None);
+        case(&decimal, "0", Some(0));
+
+        let spaces = ParseOptions::new().separators(&[' ', '\u{a0}']);
+        case(&spaces, "1 000\u{a0}000", Some(1_000_000));
+        case(&spaces, "1,000", None);
+    }
+
+    #[test]
+    fn grouping_test() {
+        fn case(s: &str, c: Result<u64, usize>) {
+            let opts = ParseOptions::new().strict_grouping(true);
+            let r = opts.parse(s).map_err(|e| {
+                assert_eq!(e.kind(), Kind::BadGrouping, "{s}");
+                e.offset()
+            });
+            assert_eq!(r, c.map(BigUInt::from), "{s}");
+        }
+
+        case("1", Ok(1));
+        case("1234567", Ok(1234567));
+        case("1,000", Ok(1000));
+        case("12,345,678", Ok(12345678));
+        case("123_456", Ok(123456));
+        case("1,,000", Err(2));
+        case("1,000,", Err(5));
+        case("10,00", Err(2));
+        case("1000,000", Err(1));
+        case("1,000000", Err(5));
+        case("1,000,0000", Err(1));
+        case("0xff_ffff", Err(4));
+    }
+
+    #[test]
+    fn error_test() {
+        fn case(s: &str, kind: Kind, offset: usize, found: Option<char>, msg: &str) {
+            let opts = ParseOptions::new().sign(true).trim_whitespace(true);
+            let e = opts.parse(s).unwrap_err();
+            assert_eq!(
+                (e.kind(), e.offset(), e.found()),
+                (kind, offset, found),
+                "{s}"
+            );
+            assert_eq!(e.to_string(), msg, "{s}");
+        }
+
+        case("", Kind::Empty, 0, None, "empty string");
+        case(" + ", Kind::Empty, 2, None, "missing digits at byte 2");
+        case("0x", Kind::Empty, 2, None, "missing digits at byte 2");
+        case(
+            " -1",
+            Kind::NotStartingWithDigit,
+            1,
+            Some('-'),
+            "number does not start with digit, found `-` at byte 1",
+        );
+        case(
+            "  00",
+            Kind::LeadingZero,
+            2,
+            Some('0'),
+            "number contains leading zero at byte 2",
+        );
+        case(
+            "+12é4",
+            Kind::InvalidCharacter,
+            3,
+            Some('é'),
+            "number contains invalid character `é` at byte 3",
+        );
+        case(
+            "0x1g",
+            Kind::InvalidCharacter,
+            3,
+            Some('g'),
+            "number contains invalid character `g` at byte 3",
+        );
+    }
+
+    #[test]
+    fn offset_test() {
+        use crate::big_decimal::BigDecimal;
+        use crate::big_int::BigInt;
+        use crate::big_rational::{BigRational, ParseBigRationalError};
+
+        assert_eq!(BigUInt::from_str("12a").unwrap_err().offset(), 2);
+        assert_eq!(BigInt::from_str("-12a").unwrap_err().offset(), 3);
+        assert_eq!(BigDecimal::from_str("-1.2a").unwrap_err().offset(), 4);
+        match BigRational::from_str("-1/2a") {
+            Err(ParseBigRationalError::Number(e)) => assert_eq!(e.offset(), 4),
+            r => panic!("{r:?}"),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Primality testing and prime generation for `BigUInt`.
+
+use super::{kernel, BigRng, BigUInt, MontgomeryContext, XorShift64};
+
+/// Primes below 256, used for trial division before Miller–Rabin.
+const SMALL_PRIMES: [u64; 54] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251,
+];
+
+/// Miller–Rabin rounds used by `next_prime` and `gen_prime`.
+const PRIME_ROUNDS: usize = 32;
+
+impl BigUInt {
+    /// Returns `false` if the number is composite, and `true` if it is prime
+    /// with error probability at most `4^-rounds`.
+    /// Small factors are found by trial division first.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let p = BigUInt::from_str("170141183460469231731687303715884105727").unwrap();
+    /// assert!(p.is_probable_prime(20));
+    /// assert!(!BigUInt::from(561u64).is_probable_prime(20));
+    /// ```
+    pub fn is_probable_prime(&self, rounds: usize) -> bool {
+        if *self < BigUInt::from(2u64) {
+            return false;
+        }
+
+        for p in SMALL_PRIMES {
+            if kernel::rem_limb(&self.inner, p) == 0 {
+                return self.inner[..] == [p];
+            }
+        }
+
+        if self.inner.len() == 1 && self.inner[0] < 256 * 256 {
+            return true;
+        }
+
+        self.miller_rabin(rounds)
+    }
+
+    /// Miller–Rabin test for an odd number above `3`.
+    fn miller_rabin(&self, rounds: usize) -> bool {
+        let ctx = MontgomeryContext::new(self).unwrap();
+        let one = ctx.to_montgomery(&BigUInt::one());
+        let n1 = self - &BigUInt::one();
+        let minus_one = ctx.to_montgomery(&n1);
+
+        let s = n1.trailing_zeros().unwrap();
+        let d = &n1 >> s;
+
+        // Bases are drawn from a generator seeded by the number itself,
+        // so the answer for a given input never changes.
+        let mut rng = XorShift64::new(self.inner[0] ^ self.inner.len() as u64);
+
+        'witness: for i in 0..rounds {
+            let a = if i == 0 {
+                BigUInt::from(2u64)
+            } else {
+                BigUInt::random_range(BigUInt::from(2u64)..n1.clone(), &mut rng)
+            };
+
+            let mut x = ctx.to_montgomery(&ctx.modpow(&a, &d));
+            if x == one || x == minus_one {
+                continue;
+            }
+
+            for _ in 1..s {
+                x = ctx.square(&x);
+                if x == minus_one {
+                    continue 'witness;
+                }
+                if x == one {
+                    return false;
+                }
+            }
+
+            return false;
+        }
+
+        true
+    }
+
+    /// Smallest probable prime greater than `self`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(100u64);
+    /// assert_eq!(a.next_prime(), BigUInt::from(101u64));
+    /// ```
+    pub fn next_prime(&self) -> BigUInt {
+        let two = BigUInt::from(2u64);
+
+        if *self < two {
+            return two;
+        }
+
+        let mut n = self + &BigUInt::one();
+        if !n.bit(0) {
+            n = &n + &BigUInt::one();
+        }
+
+        while !n.is_probable_prime(PRIME_ROUNDS) {
+            n = &n + &two;
+        }
+
+        n
+    }
+
+    /// Random probable prime with exactly `bits` bits.
+    /// The same `rng` state always gives the same prime.
+    ///
+    /// Panics if `bits` is less than `2`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut rng = XorShift64::new(1);
+    /// let p = BigUInt::gen_prime(128, &mut rng);
+    /// assert_eq!(p.bit_length(), 128);
+    /// ```
+    pub fn gen_prime(bits: usize, rng: &mut impl BigRng) -> BigUInt {
+        assert!(bits >= 2, "a prime needs at least 2 bits");
+
+        loop {
+            let mut n = BigUInt::random_bits(bits, rng);
+            n.set_bit(bits - 1);
+            n.set_bit(0);
+
+            if n.is_probable_prime(PRIME_ROUNDS) {
+                return n;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::big_uint::{BigUInt, XorShift64};
+
+    #[test]
+    fn is_probable_prime_test() {
+        fn case(a: &str, expected: bool) {
+            let a = BigUInt::from_str(a).unwrap();
+            assert_eq!(a.is_probable_prime(20), expected, "{a}");
+        }
+
+        case("0", false);
+        case("1", false);
+        case("2", true);
+        case("3", true);
+        case("4", false);
+        case("251", true);
+        case("257", true);
+        case("65537", true);
+        case("561", false);
+        case("41041", false);
+        case("3215031751", false);
+        case("18446744073709551557", true);
+        case("18446744073709551629", true);
+        case("618970019642690137449562111", true);
+        case("170141183460469231731687303715884105727", true);
+        case("170141183460469231731687303715884105729", false);
+        // product of two 64-bit primes
+        case("340282366920938460843936948965011886881", false);
+    }
+
+    #[test]
+    fn next_prime_test() {
+        fn case(a: &str, c: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            assert_eq!(a.next_prime(), BigUInt::from_str(c).unwrap());
+        }
+
+        case("0", "2");
+        case("2", "3");
+        case("3", "5");
+        case("100", "101");
+        case("18446744073709551616", "18446744073709551629");
+        case(
+            "10000000000000000000000000000000000000000",
+            "10000000000000000000000000000000000000121",
+        );
+    }
+
+    #[test]
+    fn gen_prime_test() {
+        for bits in [2, 3, 16, 64, 65, 200] {
+            let p = BigUInt::gen_prime(bits, &mut XorShift64::new(bits as u64));
+            assert_eq!(p.bit_length(), bits);
+            assert!(p.is_probable_prime(20));
+
+            let q = BigUInt::gen_prime(bits, &mut XorShift64::new(bits as u64));
+            assert_eq!(p, q);
+        }
+    }
+}
@@ -0,0 +1,423 @@
+//! Parsing and formatting `BigUInt` in bases from 2 to 36.
+//!
+//! Digits are grouped into chunks of as many digits as fit in a limb.
+//! Parsing joins the chunks pairwise, multiplying by `base^(2^k)` at level
+//! `k`, and formatting splits the number by the same powers. The splits use
+//! Barrett reduction with reciprocals refined by Newton's method, so both
+//! directions run in about `O(M(n) log n)` rather than quadratic time.
+
+use std::fmt::Formatter;
+use std::{fmt, thread};
+
+use super::{kernel, par, BigUInt, ParseBigUIntError, ParseOptions};
+
+/// Numbers up to this many limbs are formatted by repeated single-limb division.
+const DC_THRESHOLD: usize = 32;
+
+/// Largest power of `radix` that fits in a limb, and its exponent.
+fn big_base(radix: u32) -> (u64, usize) {
+    let mut base = radix as u64;
+    let mut digits = 1;
+
+    while let Some(x) = base.checked_mul(radix as u64) {
+        base = x;
+        digits += 1;
+    }
+
+    (base, digits)
+}
+
+/// Splits a `0x`, `0o` or `0b` prefix off `s` and returns the matching radix.
+pub(super) fn strip_prefix(s: &str) -> Option<(u32, &str)> {
+    let radix = match s.get(..2)? {
+        "0x" | "0X" => 16,
+        "0o" | "0O" => 8,
+        "0b" | "0B" => 2,
+        _ => return None,
+    };
+
+    Some((radix, &s[2..]))
+}
+
+/// Number from its digits in base `radix`, most significant first.
+pub(super) fn from_digits(v: &[u32], radix: u32) -> BigUInt {
+    let (base, digits) = big_base(radix);
+    let chunks = v
+        .rchunks(digits)
+        .map(|c| BigUInt::from(c.iter().fold(0, |a, &d| a * radix as u64 + d as u64)))
+        .collect();
+
+    from_chunks(chunks, base)
+}
+
+/// Joins chunks, least significant first, each worth `base` times the one before.
+fn from_chunks(mut v: Vec<BigUInt>, base: u64) -> BigUInt {
+    let mut power = BigUInt::from(base);
+
+    while v.len() > 1 {
+        let mut joined = Vec::with_capacity(v.len().div_ceil(2));
+        let mut it = v.into_iter();
+
+        while let Some(lo) = it.next() {
+            joined.push(match it.next() {
+                Some(hi) => &(&hi * &power) + &lo,
+                None => lo,
+            });
+        }
+
+        v = joined;
+        if v.len() > 1 {
+            power = &power * &power;
+        }
+    }
+
+    v.pop().unwrap_or_else(BigUInt::zero)
+}
+
+/// `base^(2^k)` together with what is needed to divide by it without long division.
+struct Power {
+    p: BigUInt,
+    bits: usize,
+    /// `floor(2^(2 * bits) / p)`
+    mu: BigUInt,
+}
+
+impl Power {
+    fn new(base: u64) -> Power {
+        let p = BigUInt::from(base);
+        let bits = p.bit_length();
+        let mu = &(&BigUInt::one() << (2 * bits)) / &p;
+        Power { p, bits, mu }
+    }
+
+    /// The power `p = self.p^2`, with its reciprocal derived from this one.
+    fn square(&self, p: BigUInt) -> Power {
+        let bits = p.bit_length();
+        let one = BigUInt::one();
+        let s = &one << (2 * bits);
+
+        // Squaring the old reciprocal gets about half of the bits right,
+        // and one Newton step, `y (2 - p y / s)`, gets nearly all of them.
+        let y = &(&self.mu * &self.mu) >> (4 * self.bits - 2 * bits);
+        let mut mu = &(&y << 1) - &(&(&p * &(&y * &y)) >> (2 * bits));
+
+        let mut t = &p * &mu;
+        while t > s {
+            mu = &mu - &one;
+            t = &t - &p;
+        }
+        let mut r = &s - &t;
+        while r >= p {
+            mu = &mu + &one;
+            r = &r - &p;
+        }
+
+        Power { p, bits, mu }
+    }
+
+    /// Quotient and remainder of `x / p` for `x < p^2`, by Barrett reduction.
+    fn div_rem(&self, x: &BigUInt) -> (BigUInt, BigUInt) {
+        let mut q = &(&(x >> (self.bits - 1)) * &self.mu) >> (self.bits + 1);
+        let mut r = x - &(&q * &self.p);
+
+        // The estimate is at most 2 below the quotient.
+        while r >= self.p {
+            r = &r - &self.p;
+            q = &q + &BigUInt::one();
+        }
+
+        (q, r)
+    }
+}
+
+/// Appends exactly `2^(k + 1)` chunks of `x`, least significant first,
+/// where `x < base^(2^(k + 1))` and `powers[i].p` is `base^(2^i)`.
+/// With `threads > 1` the two halves of large numbers are converted on
+/// separate threads.
+fn to_chunks(
+    x: &BigUInt,
+    k: usize,
+    powers: &[Power],
+    base: u64,
+    threads: usize,
+    out: &mut Vec<u64>,
+) {
+    if k == 0 || x.inner.len() <= DC_THRESHOLD {
+        let mut t = x.inner.clone();
+        for _ in 0..2 << k {
+            out.push(kernel::div_rem_limb(&mut t, base));
+            t.trim();
+        }
+        return;
+    }
+
+    let (q, r) = powers[k].div_rem(x);
+    if threads > 1 && x.inner.len() >= par::PAR_THRESHOLD {
+        let mut hi = Vec::new();
+        thread::scope(|s| {
+            s.spawn(|| to_chunks(&q, k - 1, powers, base, threads / 2, &mut hi));
+            to_chunks(&r, k - 1, powers, base, threads - threads / 2, out);
+        });
+        out.append(&mut hi);
+    } else {
+        to_chunks(&r, k - 1, powers, base, 1, out);
+        to_chunks(&q, k - 1, powers, base, 1, out);
+    }
+}
+
+impl BigUInt {
+    /// String in base `radix` to big integer. Digits above `9` are letters of
+    /// either case, `,` and `_` are ignored, and leading zeros are allowed.
+    ///
+    /// Panics if `radix` is not in `2..=36`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from_str_radix("ff_ff", 16).unwrap();
+    /// assert_eq!(a, BigUInt::from(65535u64));
+    /// ```
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BigUInt, ParseBigUIntError> {
+        assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+
+        let v = ParseOptions::new().digits(s, radix, 0, true)?;
+        Ok(from_digits(&v, radix))
+    }
+
+    /// Big integer to a string in base `radix`, with lowercase letters.
+    ///
+    /// Panics if `radix` is not in `2..=36`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(65535u64);
+    /// assert_eq!(a.to_str_radix(16), "ffff");
+    /// ```
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        self.to_str_radix_with(radix, 1)
+    }
+
+    /// `to_str_radix` that may split the work across up to `threads` threads.
+    pub(super) fn to_str_radix_with(&self, radix: u32, threads: usize) -> String {
+        assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+
+        if self.inner.is_empty() {
+            return "0".to_string();
+        }
+
+        let digit = |d: u64| char::from_digit(d as u32, radix).unwrap();
+        let mut v = Vec::new();
+
+        if radix.is_power_of_two() {
+            let bits = radix.trailing_zeros() as usize;
+            let mask = radix as u64 - 1;
+
+            for i in (0..self.bit_length()).step_by(bits) {
+                let (limb, shift) = (i / 64, i % 64);
+                let mut d = self.inner[limb] >> shift;
+                if shift + bits > 64 {
+                    if let Some(x) = self.inner.get(limb + 1) {
+                        d |= x << (64 - shift);
+                    }
+                }
+                v.push(digit(d & mask));
+            }
+        } else {
+            let (base, digits) = big_base(radix);
+            let mut chunks = Vec::new();
+
+            if self.inner.len() <= DC_THRESHOLD {
+                let mut t = self.inner.clone();
+                while !t.is_empty() {
+                    chunks.push(kernel::div_rem_limb(&mut t, base));
+                    t.trim();
+                }
+            } else {
+                let mut powers = vec![Power::new(base)];
+                loop {
+                    let last = powers.last().unwrap();
+                    let p = &last.p * &last.p;
+                    if p > *self {
+                        break;
+                    }
+                    powers.push(last.square(p));
+                }
+
+                to_chunks(self, powers.len() - 1, &powers, base, threads, &mut chunks);
+            }
+
+            for mut r in chunks {
+                for _ in 0..digits {
+                    v.push(digit(r % radix as u64));
+                    r /= radix as u64;
+                }
+            }
+            while v.len() > 1 && v.last() == Some(&'0') {
+                v.pop();
+            }
+        }
+
+        v.into_iter().rev().collect()
+    }
+}
+
+impl fmt::LowerHex for BigUInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16))
+    }
+}
+
+impl fmt::UpperHex for BigUInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16).to_ascii_uppercase())
+    }
+}
+
+impl fmt::Octal for BigUInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0o", &self.to_str_radix(8))
+    }
+}
+
+impl fmt::Binary for BigUInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0b", &self.to_str_radix(2))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::big_uint::{kernel, BigUInt, XorShift64};
+
+    /// Decimal digits by repeated single-limb division.
+    fn naive_decimal(a: &BigUInt) -> String {
+        let mut t = a.inner.to_vec();
+        let mut chunks = Vec::new();
+
+        while !t.is_empty() {
+            chunks.push(kernel::div_rem_limb(&mut t, 10_000_000_000_000_000_000));
+            kernel::trim(&mut t);
+        }
+
+        match chunks.split_last() {
+            Some((top, rest)) => rest
+                .iter()
+                .rev()
+                .fold(top.to_string(), |s, c| s + &format!("{c:019}")),
+            None => "0".to_string(),
+        }
+    }
+
+    #[test]
+    fn from_str_radix_test() {
+        fn case(s: &str, radix: u32, c: &str) {
+            let a = BigUInt::from_str_radix(s, radix).unwrap();
+            assert_eq!(a, BigUInt::from_str(c).unwrap(), "{s} in base {radix}");
+        }
+
+        case("0", 2, "0");
+        case("ff", 16, "255");
+        case("FF_FF", 16, "65535");
+        case("00ff", 16, "255");
+        case(
+            "ffffffffffffffffffffffffffffffff",
+            16,
+            "340282366920938463463374607431768211455",
+        );
+        case("777", 8, "511");
+        case("zz", 36, "1295");
+        case(
+            "1000000000000000000000000000000000000000000000000000000000000000000",
+            2,
+            "73786976294838206464",
+        );
+
+        assert!(BigUInt::from_str_radix("", 16).is_err());
+        assert!(BigUInt::from_str_radix("_1", 16).is_err());
+        assert!(BigUInt::from_str_radix("1g", 16).is_err());
+        assert!(BigUInt::from_str_radix("2", 2).is_err());
+    }
+
+    #[test]
+    fn prefix_test() {
+        assert_eq!(BigUInt::from_str("0xff").unwrap(), BigUInt::from(255u64));
+        assert_eq!(BigUInt::from_str("0XFF").unwrap(), BigUInt::from(255u64));
+        assert_eq!(BigUInt::from_str("0o17").unwrap(), BigUInt::from(15u64));
+        assert_eq!(BigUInt::from_str("0b1_0000").unwrap(), BigUInt::from(16u64));
+        assert!(BigUInt::from_str("0x").is_err());
+        assert!(BigUInt::from_str("0xg").is_err());
+    }
+
+    #[test]
+    fn to_str_radix_test() {
+        let a = BigUInt::from_str("76345621812716237612783617236178236712378123").unwrap();
+
+        for radix in 2..=36 {
+            let s = a.to_str_radix(radix);
+            assert_eq!(BigUInt::from_str_radix(&s, radix).unwrap(), a, "{radix}");
+        }
+
+        assert_eq!(BigUInt::zero().to_str_radix(16), "0");
+        assert_eq!(BigUInt::from(255u64).to_str_radix(2), "11111111");
+        assert_eq!(a.to_str_radix(10), a.to_string());
+        assert_eq!(
+            BigUInt::from_str("340282366920938463463374607431768211455")
+                .unwrap()
+                .to_str_radix(8),
+            "3777777777777777777777777777777777777777777"
+        );
+    }
+
+    #[test]
+    fn format_test() {
+        let a = BigUInt::from(0xbeefu64);
+
+        assert_eq!(format!("{a:x}"), "beef");
+        assert_eq!(format!("{a:X}"), "BEEF");
+        assert_eq!(format!("{a:#x}"), "0xbeef");
+        assert_eq!(format!("{a:#010x}"), "0x0000beef");
+        assert_eq!(format!("{a:>8x}"), "    beef");
+        assert_eq!(format!("{a:o}"), "137357");
+        assert_eq!(format!("{a:#b}"), "0b1011111011101111");
+        assert_eq!(format!("{:x}", BigUInt::zero()), "0");
+
+        let b = BigUInt::from_str("0x1234567890abcdef1234567890abcdef").unwrap();
+        assert_eq!(format!("{b:x}"), "1234567890abcdef1234567890abcdef");
+    }
+
+    #[test]
+    fn decimal_test() {
+        let mut rng = XorShift64::new(23);
+
+        for bits in [1, 63, 64, 65, 128, 2047, 2048, 2049, 5000, 40000] {
+            for _ in 0..3 {
+                let a = BigUInt::random_bits(bits, &mut rng);
+                let s = a.to_string();
+                assert_eq!(s, naive_decimal(&a), "{bits}");
+                assert_eq!(BigUInt::from_str(&s).unwrap(), a, "{bits}");
+            }
+        }
+
+        let ten = BigUInt::from(10u64);
+        for k in [19, 38, 1000, 5000] {
+            let p = ten.pow(k as u32);
+            assert_eq!(p.to_string(), format!("1{}", "0".repeat(k)));
+            assert_eq!((&p - &BigUInt::one()).to_string(), "9".repeat(k));
+            assert_eq!(
+                BigUInt::from_str(&"9".repeat(k)).unwrap(),
+                &p - &BigUInt::one()
+            );
+        }
+    }
+
+    #[test]
+    fn large_decimal_test() {
+        let s = "1234567890".repeat(10_000);
+        let a = BigUInt::from_str(&s).unwrap();
+        assert_eq!(a.to_string(), s);
+        assert_eq!(a.to_str_radix(7).len(), 118_329);
+    }
+}
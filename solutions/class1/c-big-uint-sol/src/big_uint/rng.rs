@@ -0,0 +1,194 @@
+//! Random `BigUInt` values.
+//!
+//! Only a tiny interface is needed, so any generator can be plugged in by
+//! implementing `BigRng`. `XorShift64` is a small built-in one whose output
+//! depends only on its seed, which keeps tests reproducible.
+
+use std::ops::Range;
+
+use super::BigUInt;
+
+/// Source of uniformly distributed 64-bit words.
+pub trait BigRng {
+    fn next_u64(&mut self) -> u64;
+}
+
+/// Marsaglia's xorshift64* generator. Not suitable for cryptography.
+///
+/// ## Example
+///
+/// ```
+/// let mut rng = XorShift64::new(42);
+/// let p = BigUInt::gen_prime(64, &mut rng);
+/// ```
+#[derive(Debug, Clone)]
+pub struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    /// Generator with the given seed. A zero seed is replaced by a fixed non-zero one.
+    pub fn new(seed: u64) -> XorShift64 {
+        XorShift64 {
+            state: if seed == 0 {
+                0x9e37_79b9_7f4a_7c15
+            } else {
+                seed
+            },
+        }
+    }
+}
+
+impl BigRng for XorShift64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+impl BigUInt {
+    /// Uniformly random number in `[0, 2^bits)`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut rng = XorShift64::new(1);
+    /// let a = BigUInt::random_bits(100, &mut rng);
+    /// assert!(a.bit_length() <= 100);
+    /// ```
+    pub fn random_bits(bits: usize, rng: &mut impl BigRng) -> BigUInt {
+        let mut inner = (0..bits.div_ceil(64))
+            .map(|_| rng.next_u64())
+            .collect::<Vec<_>>();
+
+        if !bits.is_multiple_of(64) {
+            if let Some(x) = inner.last_mut() {
+                *x &= (1 << (bits % 64)) - 1;
+            }
+        }
+
+        BigUInt::from_limbs(inner)
+    }
+
+    /// Uniformly random number in `[0, bound)`.
+    /// Samples with the bit length of `bound` are drawn until one is below it,
+    /// so no value is more likely than another.
+    ///
+    /// Panics if `bound` is `0`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut rng = XorShift64::new(1);
+    /// let a = BigUInt::random_below(&BigUInt::from(6u64), &mut rng);
+    /// assert!(a < BigUInt::from(6u64));
+    /// ```
+    pub fn random_below(bound: &BigUInt, rng: &mut impl BigRng) -> BigUInt {
+        assert!(!bound.is_zero(), "cannot sample below zero");
+
+        let bits = (bound - &BigUInt::one()).bit_length();
+
+        loop {
+            let x = BigUInt::random_bits(bits, rng);
+            if x < *bound {
+                return x;
+            }
+        }
+    }
+
+    /// Uniformly random number in `range`.
+    ///
+    /// Panics if `range` is empty.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut rng = XorShift64::new(1);
+    /// let a = BigUInt::random_range(BigUInt::from(10u64)..BigUInt::from(20u64), &mut rng);
+    /// ```
+    pub fn random_range(range: Range<BigUInt>, rng: &mut impl BigRng) -> BigUInt {
+        assert!(range.start < range.end, "cannot sample empty range");
+
+        let offset = BigUInt::random_below(&(&range.end - &range.start), rng);
+        &range.start + &offset
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BigRng, XorShift64};
+    use crate::big_uint::BigUInt;
+
+    #[test]
+    fn seed_test() {
+        let a = (0..8).map({
+            let mut rng = XorShift64::new(7);
+            move |_| rng.next_u64()
+        });
+        let b = (0..8).map({
+            let mut rng = XorShift64::new(7);
+            move |_| rng.next_u64()
+        });
+        assert!(a.eq(b));
+
+        let mut zero = XorShift64::new(0);
+        assert_ne!(zero.next_u64(), 0);
+    }
+
+    #[test]
+    fn random_bits_test() {
+        let mut rng = XorShift64::new(3);
+
+        assert_eq!(BigUInt::random_bits(0, &mut rng), BigUInt::zero());
+        for bits in [1, 63, 64, 65, 200] {
+            let mut max = 0;
+            for _ in 0..64 {
+                let a = BigUInt::random_bits(bits, &mut rng);
+                max = max.max(a.bit_length());
+            }
+            assert_eq!(max, bits);
+        }
+    }
+
+    #[test]
+    fn random_below_test() {
+        let mut rng = XorShift64::new(5);
+        let mut count = [0; 3];
+
+        for _ in 0..3000 {
+            let a = BigUInt::random_below(&BigUInt::from(3u64), &mut rng);
+            count[a.limbs().first().copied().unwrap_or(0) as usize] += 1;
+        }
+        assert!(count.iter().all(|&c| (900..1100).contains(&c)), "{count:?}");
+
+        let bound = &BigUInt::one() << 130;
+        for _ in 0..100 {
+            assert!(BigUInt::random_below(&bound, &mut rng) < bound);
+        }
+        assert_eq!(
+            BigUInt::random_below(&BigUInt::one(), &mut rng),
+            BigUInt::zero()
+        );
+    }
+
+    #[test]
+    fn random_range_test() {
+        let mut rng = XorShift64::new(7);
+        let lo = &BigUInt::one() << 100;
+        let hi = &lo + &BigUInt::from(10u64);
+
+        for _ in 0..100 {
+            let a = BigUInt::random_range(lo.clone()..hi.clone(), &mut rng);
+            assert!(lo <= a && a < hi);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot sample empty range")]
+    fn random_range_empty_test() {
+        let a = BigUInt::from(10u64);
+        BigUInt::random_range(a.clone()..a, &mut XorShift64::new(1));
+    }
+}
@@ -0,0 +1,217 @@
+//! Integer roots and logarithms for `BigUInt`.
+
+use super::BigUInt;
+
+impl BigUInt {
+    /// Floor of the square root.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(99u64);
+    /// assert_eq!(a.sqrt(), BigUInt::from(9u64));
+    /// ```
+    pub fn sqrt(&self) -> BigUInt {
+        self.nth_root(2)
+    }
+
+    /// Floor of the `n`-th root, by Newton's iteration from an upper bound.
+    ///
+    /// Panics if `n` is `0`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(1_000_000u64);
+    /// assert_eq!(a.nth_root(3), BigUInt::from(100u64));
+    /// ```
+    pub fn nth_root(&self, n: u32) -> BigUInt {
+        assert!(n > 0, "zeroth root is undefined");
+
+        if n == 1 || self.inner.is_empty() {
+            return self.clone();
+        }
+        // Below `2^n`, so the root is below 2.
+        if n as usize >= self.bit_length() {
+            return BigUInt::one();
+        }
+
+        let n_big = BigUInt::from(n);
+        let n1 = BigUInt::from(n - 1);
+
+        // 2^ceil(bits / n) is never below the root.
+        let mut x = &BigUInt::one() << self.bit_length().div_ceil(n as usize);
+
+        loop {
+            let y = &(&(&n1 * &x) + &(self / &x.pow(n - 1))) / &n_big;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    /// Floor of the base 2 logarithm.
+    ///
+    /// Panics if the number is `0`.
+    pub fn ilog2(&self) -> usize {
+        assert!(
+            !self.inner.is_empty(),
+            "argument of integer logarithm must be positive"
+        );
+        self.bit_length() - 1
+    }
+
+    /// Floor of the base 10 logarithm.
+    ///
+    /// Panics if the number is `0`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let a = BigUInt::from(999u64);
+    /// assert_eq!(a.ilog10(), 2);
+    /// ```
+    pub fn ilog10(&self) -> usize {
+        self.ilog(&BigUInt::from(10u64))
+    }
+
+    /// Floor of the logarithm in `base`.
+    /// The result is estimated in floating point and then corrected exactly.
+    ///
+    /// Panics if the number is `0` or `base` is less than `2`.
+    pub fn ilog(&self, base: &BigUInt) -> usize {
+        assert!(
+            !self.inner.is_empty(),
+            "argument of integer logarithm must be positive"
+        );
+        assert!(
+            *base >= BigUInt::from(2u64),
+            "base of integer logarithm must be at least 2"
+        );
+
+        if self < base {
+            return 0;
+        }
+
+        let estimate = (self.log2_approx() / base.log2_approx()).floor() as usize;
+        let mut e = estimate.max(1);
+        let mut p = base.pow(e as u32);
+
+        while p > *self {
+            p = &p / base;
+            e -= 1;
+        }
+
+        loop {
+            let q = &p * base;
+            if q > *self {
+                return e;
+            }
+            p = q;
+            e += 1;
+        }
+    }
+
+    /// Base 2 logarithm in floating point, from the top two limbs.
+    fn log2_approx(&self) -> f64 {
+        let n = self.inner.len();
+        let top = self.inner[n - 1] as f64;
+
+        if n == 1 {
+            top.log2()
+        } else {
+            let low = self.inner[n - 2] as f64;
+            (top + low / 18_446_744_073_709_551_616.0).log2() + (64 * (n - 1)) as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::big_uint::BigUInt;
+
+    #[test]
+    fn sqrt_test() {
+        fn case(a: &str, c: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            assert_eq!(a.sqrt(), BigUInt::from_str(c).unwrap(), "{a}");
+        }
+
+        case("0", "0");
+        case("1", "1");
+        case("3", "1");
+        case("4", "2");
+        case("99", "9");
+        case("18446744073709551615", "4294967295");
+        case("18446744073709551616", "4294967296");
+        case(
+            "152415787532388367504942236884722755800955129",
+            "12345678901234567890123",
+        );
+        case(
+            "152415787532388367504942236884722755800955128",
+            "12345678901234567890122",
+        );
+    }
+
+    #[test]
+    fn nth_root_test() {
+        fn case(a: &str, n: u32, c: &str) {
+            let a = BigUInt::from_str(a).unwrap();
+            assert_eq!(a.nth_root(n), BigUInt::from_str(c).unwrap(), "{a} {n}");
+        }
+
+        case("0", 3, "0");
+        case("7", 1, "7");
+        case("7", 3, "1");
+        case("8", 3, "2");
+        case("1000000", 3, "100");
+        case("999999", 3, "99");
+        case("1267650600228229401496703205376", 100, "2");
+        case("1267650600228229401496703205375", 100, "1");
+        case("1267650600228229401496703205376", 101, "1");
+        case("1", u32::MAX, "1");
+        case("1267650600228229401496703205376", u32::MAX, "1");
+
+        let big = BigUInt::from(12345u64).pow(300);
+        assert_eq!(big.nth_root(300), BigUInt::from(12345u64));
+        assert_eq!(
+            (&big - &BigUInt::one()).nth_root(300),
+            BigUInt::from(12344u64)
+        );
+    }
+
+    #[test]
+    fn ilog_test() {
+        assert_eq!(BigUInt::one().ilog2(), 0);
+        assert_eq!(BigUInt::from(1024u64).ilog2(), 10);
+        assert_eq!(BigUInt::from(1023u64).ilog2(), 9);
+
+        assert_eq!(BigUInt::one().ilog10(), 0);
+        assert_eq!(BigUInt::from(9u64).ilog10(), 0);
+        assert_eq!(BigUInt::from(10u64).ilog10(), 1);
+
+        let ten = BigUInt::from(10u64);
+        for e in [18, 19, 20, 100, 1000] {
+            let p = ten.pow(e);
+            assert_eq!(p.ilog10(), e as usize);
+            assert_eq!((&p - &BigUInt::one()).ilog10(), e as usize - 1);
+            assert_eq!((&p + &BigUInt::one()).ilog10(), e as usize);
+        }
+
+        let three = BigUInt::from(3u64);
+        let p = three.pow(500);
+        assert_eq!(p.ilog(&three), 500);
+        assert_eq!((&p - &BigUInt::one()).ilog(&three), 499);
+        assert_eq!(BigUInt::from(5u64).ilog(&BigUInt::from(6u64)), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "argument of integer logarithm must be positive")]
+    fn ilog_zero_test() {
+        BigUInt::zero().ilog10();
+    }
+}
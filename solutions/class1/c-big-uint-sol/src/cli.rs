@@ -0,0 +1,292 @@
+//! Command line options and the output formats of the calculator.
+
+use std::fmt::Write;
+
+use crate::expr::{Error, Value};
+
+pub const USAGE: &str = "\
+usage: c-big-uint-sol [options] [file]
+
+Evaluates the expressions in `file`, one per line, or those given with `-e`.
+Without either, lines are read from the standard input until its end.
+Blank lines and lines starting with `#` are skipped.
+
+options:
+  -e, --expr <expr>       evaluate `expr`; may be repeated
+  -f, --format <format>   `plain` (default), `json` or `csv`
+  -b, --base <base>       print results in base 2 to 36 (default 10)
+  -j, --threads <n>       threads for printing large results (default: all CPUs)
+  -h, --help              print this message";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    Plain,
+    /// One `{"expr":...,"result":...,"error":...}` object per line.
+    Json,
+    /// `expr,result,error` rows after a header.
+    Csv,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Options {
+    pub exprs: Vec<String>,
+    pub file: Option<String>,
+    pub format: Format,
+    pub base: u32,
+    /// `0` for the default of `big_uint::threads`.
+    pub threads: usize,
+    pub help: bool,
+}
+
+impl Options {
+    /// Options from the arguments after the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+        let mut opts = Options {
+            exprs: Vec::new(),
+            file: None,
+            format: Format::Plain,
+            base: 10,
+            threads: 0,
+            help: false,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("`{arg}` needs a value"));
+
+            match arg.as_str() {
+                "-e" | "--expr" => opts.exprs.push(value()?),
+                "-f" | "--format" => {
+                    opts.format = match value()?.as_str() {
+                        "plain" => Format::Plain,
+                        "json" => Format::Json,
+                        "csv" => Format::Csv,
+                        f => return Err(format!("unknown format `{f}`")),
+                    }
+                }
+                "-b" | "--base" => {
+                    let b = value()?;
+                    opts.base = match b.parse() {
+                        Ok(b @ 2..=36) => b,
+                        _ => return Err(format!("base must be in 2..=36, found `{b}`")),
+                    }
+                }
+                "-j" | "--threads" => {
+                    let n = value()?;
+                    opts.threads = match n.parse() {
+                        Ok(n @ 1..) => n,
+                        _ => return Err(format!("threads must be positive, found `{n}`")),
+                    }
+                }
+                "-h" | "--help" => opts.help = true,
+                _ if arg.starts_with('-') && arg.len() > 1 => {
+                    return Err(format!("unknown option `{arg}`"))
+                }
+                _ if opts.file.is_some() => return Err(format!("unexpected argument `{arg}`")),
+                _ => opts.file = Some(arg),
+            }
+        }
+
+        if !opts.exprs.is_empty() && opts.file.is_some() {
+            return Err("`-e` cannot be combined with a file".to_string());
+        }
+        Ok(opts)
+    }
+}
+
+/// Formats each evaluated line in the chosen format.
+pub struct Printer {
+    pub format: Format,
+    pub base: u32,
+}
+
+impl Printer {
+    /// Text printed before the first record, if any.
+    pub fn header(&self) -> Option<&'static str> {
+        match self.format {
+            Format::Csv => Some("expr,result,error"),
+            _ => None,
+        }
+    }
+
+    /// The record for `expr`, found on line `line` of a file if given.
+    /// Plain results go to the standard output and plain errors to the
+    /// standard error; the `bool` tells which.
+    pub fn record(
+        &self,
+        expr: &str,
+        line: Option<usize>,
+        result: &Result<Value, Error>,
+    ) -> (String, bool) {
+        let value = result.as_ref().ok().map(|v| v.to_str_radix(self.base));
+        let error = result.as_ref().err().map(|e| e.to_string());
+
+        match self.format {
+            Format::Plain => match (value, error) {
+                (Some(v), _) => (v, true),
+                (_, e) => {
+                    let at = line.map_or(String::new(), |n| format!("line {n}: "));
+                    (format!("error: {at}{}", e.unwrap_or_default()), false)
+                }
+            },
+            Format::Json => {
+                let field = |s: Option<String>| s.as_deref().map_or("null".to_string(), json_str);
+                let s = format!(
+                    "{{\"expr\":{},\"result\":{},\"error\":{}}}",
+                    json_str(expr),
+                    field(value),
+                    field(error)
+                );
+                (s, true)
+            }
+            Format::Csv => {
+                let field = |s: Option<String>| csv_field(s.as_deref().unwrap_or(""));
+                let s = format!("{},{},{}", csv_field(expr), field(value), field(error));
+                (s, true)
+            }
+        }
+    }
+}
+
+/// `s` as a JSON string literal.
+fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// `s` as a CSV field, quoted only when it has to be.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) || s.trim() != s {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{csv_field, json_str, Format, Options, Printer};
+    use crate::expr::Env;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn parse_test() {
+        let o = parse(&["-e", "1 + 1", "--expr", "2", "-f", "json", "--base", "16"]).unwrap();
+        assert_eq!(o.exprs, ["1 + 1", "2"]);
+        assert_eq!((o.format, o.base, o.file), (Format::Json, 16, None));
+        assert_eq!(o.threads, 0);
+        assert_eq!(parse(&["-j", "4"]).unwrap().threads, 4);
+
+        let o = parse(&["suite.txt", "--format", "csv"]).unwrap();
+        assert_eq!(o.file.as_deref(), Some("suite.txt"));
+        assert_eq!((o.format, o.base), (Format::Csv, 10));
+        assert!(parse(&["-h"]).unwrap().help);
+        // A leading `-` in a value is part of the expression.
+        assert_eq!(parse(&["-e", "-1"]).unwrap().exprs, ["-1"]);
+
+        assert_eq!(parse(&["-e"]), Err("`-e` needs a value".to_string()));
+        assert_eq!(
+            parse(&["-f", "xml"]),
+            Err("unknown format `xml`".to_string())
+        );
+        assert_eq!(
+            parse(&["-b", "37"]),
+            Err("base must be in 2..=36, found `37`".to_string())
+        );
+        assert_eq!(
+            parse(&["--threads", "0"]),
+            Err("threads must be positive, found `0`".to_string())
+        );
+        assert_eq!(parse(&["-x"]), Err("unknown option `-x`".to_string()));
+        assert_eq!(
+            parse(&["a", "b"]),
+            Err("unexpected argument `b`".to_string())
+        );
+        assert_eq!(
+            parse(&["-e", "1", "a"]),
+            Err("`-e` cannot be combined with a file".to_string())
+        );
+    }
+
+    #[test]
+    fn record_test() {
+        fn case(format: Format, base: u32, expr: &str, c: &str, stdout: bool) {
+            let p = Printer { format, base };
+            let r = Env::new().eval_line(expr);
+            assert_eq!(p.record(expr, None, &r), (c.to_string(), stdout), "{expr}");
+        }
+
+        case(Format::Plain, 10, "255", "255", true);
+        case(Format::Plain, 16, "-255", "-ff", true);
+        case(Format::Plain, 16, "1 < 2", "true", true);
+        case(
+            Format::Plain,
+            10,
+            "1 / 0",
+            "error: column 3: division by zero",
+            false,
+        );
+        case(
+            Format::Json,
+            2,
+            "5",
+            r#"{"expr":"5","result":"101","error":null}"#,
+            true,
+        );
+        case(
+            Format::Json,
+            10,
+            "x",
+            r#"{"expr":"x","result":null,"error":"column 1: unknown variable `x`"}"#,
+            true,
+        );
+        case(Format::Csv, 10, "gcd(4, 6)", "\"gcd(4, 6)\",2,", true);
+        case(
+            Format::Csv,
+            10,
+            "1 %",
+            "1 %,,column 4: unexpected end of input",
+            true,
+        );
+    }
+
+    #[test]
+    fn line_test() {
+        let p = Printer {
+            format: Format::Plain,
+            base: 10,
+        };
+        let r = Env::new().eval_line("1 / 0");
+        assert_eq!(
+            p.record("1 / 0", Some(4), &r),
+            (
+                "error: line 4: column 3: division by zero".to_string(),
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(json_str("a\"b\\c\n\u{1}"), r#""a\"b\\c\n\u0001""#);
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field(" 1"), "\" 1\"");
+    }
+}
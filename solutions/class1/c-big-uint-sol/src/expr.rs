@@ -0,0 +1,354 @@
+//! Expression language of the calculator.
+//!
+//! A line is either an expression or an assignment `name = expression`.
+//! Expressions combine integers with `+ - * / % ^`, postfix `!`, parentheses,
+//! comparisons and the functions `gcd(a, b)`, `sqrt(a)` and
+//! `modpow(b, e, m)`. Every line that succeeds is also stored in `ans`.
+//!
+//! ## Examples
+//!
+//! ```
+//! let mut env = Env::new();
+//! assert_eq!(env.eval_line("x = 2^100").unwrap().to_string(), "1267650600228229401496703205376");
+//! assert_eq!(env.eval_line("ans % 1000 + 5!").unwrap().to_string(), "496");
+//! assert_eq!(env.eval_line("1 / (x - x)").unwrap_err().to_string(), "column 3: division by zero");
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use crate::big_int::BigInt;
+use crate::big_uint::BigUInt;
+use parse::{Expr, Node, Parser, Stmt};
+
+mod parse;
+mod token;
+
+/// Largest `n` accepted by `n!`.
+const FACTORIAL_LIMIT: u64 = 100_000;
+
+/// Largest number of bits a power may have, to keep typos like `10^10^10`
+/// from exhausting memory.
+const POW_LIMIT_BITS: u64 = 1 << 26;
+
+/// A parse or arithmetic error and the 1-based column it was found at.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Error {
+    pub col: usize,
+    pub msg: String,
+}
+
+impl Error {
+    fn new(col: usize, msg: impl Into<String>) -> Error {
+        Error {
+            col,
+            msg: msg.into(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column {}: {}", self.col, self.msg)
+    }
+}
+
+/// Result of an expression: an integer, or the outcome of a comparison.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Value {
+    Int(BigInt),
+    Bool(bool),
+}
+
+impl Value {
+    /// Integers in base `radix`, with a leading `-` if negative. Large
+    /// decimals are converted on up to `big_uint::threads()` threads.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits = |n: &BigUInt| match radix {
+            10 => n.par_to_string(),
+            _ => n.to_str_radix(radix),
+        };
+        match self {
+            Value::Int(n) if n.is_negative() => format!("-{}", digits(n.magnitude())),
+            Value::Int(n) => digits(n.magnitude()),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{n}"),
+            Value::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+/// Variables kept from one line to the next.
+#[derive(Debug, Default)]
+pub struct Env {
+    vars: HashMap<String, Value>,
+}
+
+impl Env {
+    pub fn new() -> Env {
+        Env::default()
+    }
+
+    /// Evaluates one line, updating the variables and `ans` if it succeeds.
+    pub fn eval_line(&mut self, line: &str) -> Result<Value, Error> {
+        let stmt = Parser::new(token::tokenize(line)?).statement()?;
+
+        let v = match stmt {
+            Stmt::Assign(name, node) => {
+                let v = self.eval(&node)?;
+                self.vars.insert(name, v.clone());
+                v
+            }
+            Stmt::Expr(node) => self.eval(&node)?,
+        };
+
+        self.vars.insert("ans".to_string(), v.clone());
+        Ok(v)
+    }
+
+    fn eval(&self, node: &Node) -> Result<Value, Error> {
+        let col = node.col;
+
+        Ok(match &node.expr {
+            Expr::Num(n) => Value::Int(n.clone()),
+            Expr::Var(name) => self
+                .vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| Error::new(col, format!("unknown variable `{name}`")))?,
+            Expr::Neg(x) => Value::Int(-self.int(x)?),
+            Expr::Fact(x) => Value::Int(factorial(&self.int(x)?, col)?),
+            Expr::Binary(op, a, b) => self.binary(op, a, b, col)?,
+            Expr::Call(name, args) => Value::Int(self.call(name, args, col)?),
+        })
+    }
+
+    /// The value of `node`, which has to be an integer.
+    fn int(&self, node: &Node) -> Result<BigInt, Error> {
+        match self.eval(node)? {
+            Value::Int(n) => Ok(n),
+            v => Err(Error::new(
+                node.col,
+                format!("expected a number, found `{v}`"),
+            )),
+        }
+    }
+
+    fn binary(&self, op: &str, a: &Node, b: &Node, col: usize) -> Result<Value, Error> {
+        match op {
+            "==" => return Ok(Value::Bool(self.eval(a)? == self.eval(b)?)),
+            "!=" => return Ok(Value::Bool(self.eval(a)? != self.eval(b)?)),
+            _ => {}
+        }
+
+        let (a, b) = (self.int(a)?, self.int(b)?);
+        let div_zero = || Error::new(col, "division by zero");
+
+        Ok(match op {
+            "<" => Value::Bool(a < b),
+            "<=" => Value::Bool(a <= b),
+            ">" => Value::Bool(a > b),
+            ">=" => Value::Bool(a >= b),
+            "+" => Value::Int(&a + &b),
+            "-" => Value::Int(&a - &b),
+            "*" => Value::Int(&a * &b),
+            "/" => Value::Int(a.checked_div(&b).ok_or_else(div_zero)?),
+            "%" => Value::Int(a.checked_rem(&b).ok_or_else(div_zero)?),
+            "^" => Value::Int(pow(&a, &b, col)?),
+            _ => unreachable!("parser produced operator `{op}`"),
+        })
+    }
+
+    fn call(&self, name: &str, args: &[Node], col: usize) -> Result<BigInt, Error> {
+        let arity = match name {
+            "gcd" => 2,
+            "sqrt" => 1,
+            "modpow" => 3,
+            _ => return Err(Error::new(col, format!("unknown function `{name}`"))),
+        };
+        if args.len() != arity {
+            return Err(Error::new(
+                col,
+                format!("`{name}` takes {arity} arguments, found {}", args.len()),
+            ));
+        }
+
+        let v = args
+            .iter()
+            .map(|a| self.int(a))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(match (name, &v[..]) {
+            ("gcd", [a, b]) => a.magnitude().gcd(b.magnitude()).into(),
+            ("sqrt", [a]) if a.is_negative() => {
+                return Err(Error::new(col, "square root of negative number"))
+            }
+            ("sqrt", [a]) => a.magnitude().sqrt().into(),
+            ("modpow", [_, _, m]) if m.is_zero() => {
+                return Err(Error::new(col, "division by zero"))
+            }
+            ("modpow", [_, e, _]) if e.is_negative() => {
+                return Err(Error::new(col, "negative exponent"))
+            }
+            ("modpow", [b, e, m]) => {
+                let m = m.magnitude();
+                let b = b.rem_euclid(&BigInt::from(m.clone()));
+                b.magnitude().modpow(e.magnitude(), m).into()
+            }
+            _ => unreachable!("arity was checked"),
+        })
+    }
+}
+
+/// `a^b` for a non-negative `b` whose result is not absurdly large.
+fn pow(a: &BigInt, b: &BigInt, col: usize) -> Result<BigInt, Error> {
+    if b.is_negative() {
+        return Err(Error::new(col, "negative exponent"));
+    }
+
+    // 0, 1 and -1 stay small whatever the exponent.
+    if a.magnitude() <= &BigUInt::one() {
+        let mag = if a.is_zero() && !b.is_zero() {
+            BigUInt::zero()
+        } else {
+            BigUInt::one()
+        };
+        return Ok(BigInt::from_parts(
+            a.is_negative() && b.magnitude().bit(0),
+            mag,
+        ));
+    }
+
+    let e = u32::try_from(b.magnitude()).map_err(|_| Error::new(col, "exponent too large"))?;
+    let bits = a.magnitude().bit_length().saturating_sub(1) as u64;
+    if bits * e as u64 > POW_LIMIT_BITS {
+        return Err(Error::new(col, "result too large"));
+    }
+
+    Ok(BigInt::from_parts(
+        a.is_negative() && e % 2 == 1,
+        a.magnitude().pow(e),
+    ))
+}
+
+fn factorial(n: &BigInt, col: usize) -> Result<BigInt, Error> {
+    if n.is_negative() {
+        return Err(Error::new(col, "factorial of negative number"));
+    }
+
+    match u64::try_from(n.magnitude()) {
+        Ok(n) if n <= FACTORIAL_LIMIT => Ok(product(1, n + 1).into()),
+        _ => Err(Error::new(col, "factorial argument too large")),
+    }
+}
+
+/// `lo * (lo + 1) * ... * (hi - 1)`, halving the range so that the big
+/// multiplications are balanced.
+fn product(lo: u64, hi: u64) -> BigUInt {
+    if hi - lo <= 16 {
+        return (lo..hi).fold(BigUInt::one(), |acc, x| acc * x);
+    }
+
+    let mid = lo + (hi - lo) / 2;
+    &product(lo, mid) * &product(mid, hi)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Env;
+
+    #[test]
+    fn eval_test() {
+        fn case(input: &str, c: Result<&str, (usize, &str)>) {
+            let r = Env::new().eval_line(input);
+            let r = r.as_ref().map(|v| v.to_string());
+            let r = r.as_deref().map_err(|e| (e.col, e.msg.as_str()));
+            assert_eq!(r, c, "{input}");
+        }
+
+        case("100 + 100", Ok("200"));
+        case("100 - 250", Ok("-150"));
+        case("-7 / 2", Ok("-3"));
+        case("100 < 250", Ok("true"));
+        case("2 + 3 * 4 - 10 % 4", Ok("12"));
+        case("(2 + 3) * 4", Ok("20"));
+        case("2 ^ 3 ^ 2", Ok("512"));
+        case("-2 ^ 2", Ok("-4"));
+        case("(-2) ^ 3", Ok("-8"));
+        case("0 ^ 0", Ok("1"));
+        case("1 ^ 10^10", Ok("1"));
+        case("0 ^ 10^10", Ok("0"));
+        case("(-1) ^ 10^10", Ok("1"));
+        case("(-1) ^ (10^10 + 1)", Ok("-1"));
+        case("-1 ^ 10^10", Ok("-1"));
+        case("20!", Ok("2432902008176640000"));
+        case("0! + 1!", Ok("2"));
+        case("gcd(12, -18)", Ok("6"));
+        case("sqrt(10^40 + 1)", Ok("100000000000000000000"));
+        case("modpow(4, 13, 497)", Ok("445"));
+        case("modpow(-4, 13, 497)", Ok("52"));
+        case("1 + 1 == 2", Ok("true"));
+        case("(1 < 2) == (3 < 4)", Ok("true"));
+        case("0xff + 1_000", Ok("1255"));
+
+        case("1 / 0", Err((3, "division by zero")));
+        case("1 % (2 - 2)", Err((3, "division by zero")));
+        case("1 +", Err((4, "unexpected end of input")));
+        case("1 + x", Err((5, "unknown variable `x`")));
+        case("2 ^ -1", Err((3, "negative exponent")));
+        case("10 ^ 10 ^ 10", Err((4, "exponent too large")));
+        case("3 ^ 100000000", Err((3, "result too large")));
+        case("(-1)!", Err((5, "factorial of negative number")));
+        case("1000000!", Err((8, "factorial argument too large")));
+        case("sqrt(-4)", Err((1, "square root of negative number")));
+        case("modpow(2, 3, 0)", Err((1, "division by zero")));
+        case("gcd(1)", Err((1, "`gcd` takes 2 arguments, found 1")));
+        case("log(8)", Err((1, "unknown function `log`")));
+        case("(1 < 2) + 1", Err((4, "expected a number, found `true`")));
+    }
+
+    #[test]
+    fn depth_test() {
+        let mut env = Env::new();
+        let r = env.eval_line(&("1 + ".repeat(255) + "1"));
+        assert_eq!(r.map(|v| v.to_string()).as_deref(), Ok("256"));
+
+        for s in ["1+".repeat(2000) + "1", "3".to_string() + &"!".repeat(5000)] {
+            let e = env.eval_line(&s).unwrap_err();
+            assert_eq!(e.msg, "expression nested too deeply");
+        }
+    }
+
+    #[test]
+    fn session_test() {
+        let mut env = Env::new();
+        let mut run = |s: &str| env.eval_line(s).map(|v| v.to_string());
+
+        assert!(run("ans").is_err());
+        assert_eq!(
+            run("x = 2^100").as_deref(),
+            Ok("1267650600228229401496703205376")
+        );
+        assert_eq!(run("ans - x").as_deref(), Ok("0"));
+        assert_eq!(run("y = x / 2^98 + ans").as_deref(), Ok("4"));
+        assert_eq!(run("y! / ans").as_deref(), Ok("6"));
+        assert!(run("z = 1 / 0").is_err());
+        assert!(run("z").is_err());
+        assert_eq!(run("ans").as_deref(), Ok("6"));
+    }
+
+    #[test]
+    fn factorial_test() {
+        let a = Env::new().eval_line("1000!").unwrap().to_string();
+        assert_eq!(a.len(), 2568);
+        assert!(a.starts_with("402387260077093773543702433923003985719374864210"));
+        assert!(a.ends_with(&"0".repeat(249)));
+    }
+}
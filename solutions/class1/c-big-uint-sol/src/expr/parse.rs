@@ -0,0 +1,315 @@
+//! Precedence-climbing parser from tokens to an expression tree.
+//!
+//! From loosest to tightest: comparisons, `+ -`, `* / %`, unary `-`, `^`
+//! (right associative) and postfix `!`. So `-2^2` is `-4` and `2^3^2` is
+//! `2^9`.
+
+use super::token::{Tok, Token};
+use super::Error;
+use crate::big_int::BigInt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Expr {
+    Num(BigInt),
+    Var(String),
+    Neg(Box<Node>),
+    Fact(Box<Node>),
+    Binary(&'static str, Box<Node>, Box<Node>),
+    Call(String, Vec<Node>),
+}
+
+/// An expression and the column it is reported at: the operator for
+/// operations, the name for variables and calls.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Node {
+    pub(super) expr: Expr,
+    pub(super) col: usize,
+    /// Nodes on the longest path down to a leaf, this one included.
+    height: usize,
+}
+
+impl Node {
+    /// Node for `expr`, or an error if the tree would get deeper than
+    /// `MAX_DEPTH`, as evaluating and dropping it both recurse.
+    fn new(expr: Expr, col: usize) -> Result<Node, Error> {
+        let below = match &expr {
+            Expr::Num(_) | Expr::Var(_) => 0,
+            Expr::Neg(x) | Expr::Fact(x) => x.height,
+            Expr::Binary(_, a, b) => a.height.max(b.height),
+            Expr::Call(_, args) => args.iter().map(|a| a.height).max().unwrap_or(0),
+        };
+        if below == MAX_DEPTH {
+            return Err(Error::new(col, "expression nested too deeply"));
+        }
+
+        Ok(Node {
+            expr,
+            col,
+            height: below + 1,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Stmt {
+    Assign(String, Node),
+    Expr(Node),
+}
+
+/// Binding power of unary minus, between `* / %` and `^`.
+const NEG_PREC: u8 = 4;
+
+/// Deepest nesting of subexpressions and of the tree built from them, so
+/// that a line of thousands of `(` or `+` is an error rather than a stack
+/// overflow.
+const MAX_DEPTH: usize = 256;
+
+/// Binding power and right associativity of a binary operator.
+fn binary(tok: &Tok) -> Option<(&'static str, u8, bool)> {
+    let Tok::Sym(s) = tok else {
+        return None;
+    };
+    let (prec, right) = match *s {
+        "==" | "!=" | "<" | "<=" | ">" | ">=" => (1, false),
+        "+" | "-" => (2, false),
+        "*" | "/" | "%" => (3, false),
+        "^" => (5, true),
+        _ => return None,
+    };
+    Some((s, prec, right))
+}
+
+pub(super) struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    pub(super) fn new(tokens: Vec<Token>) -> Parser {
+        Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> Token {
+        let t = self.tokens[self.pos].clone();
+        if t.tok != Tok::End {
+            self.pos += 1;
+        }
+        t
+    }
+
+    fn expect(&mut self, sym: &'static str) -> Result<(), Error> {
+        let t = self.next();
+        if t.tok == Tok::Sym(sym) {
+            Ok(())
+        } else {
+            Err(Error::new(
+                t.col,
+                format!("expected `{sym}`, found {}", t.tok),
+            ))
+        }
+    }
+
+    /// `name = expr` or `expr`, followed by the end of the line.
+    pub(super) fn statement(&mut self) -> Result<Stmt, Error> {
+        let stmt = match &self.tokens[self.pos..] {
+            [Token {
+                tok: Tok::Ident(name),
+                ..
+            }, Token {
+                tok: Tok::Sym("="), ..
+            }, ..] => {
+                let name = name.clone();
+                self.pos += 2;
+                Stmt::Assign(name, self.expr(0)?)
+            }
+            _ => Stmt::Expr(self.expr(0)?),
+        };
+
+        let t = self.next();
+        if t.tok != Tok::End {
+            return Err(Error::new(t.col, format!("unexpected {}", t.tok)));
+        }
+        Ok(stmt)
+    }
+
+    /// Operators binding at least as tightly as `min_prec`. Every nested
+    /// subexpression goes through here, so this is where depth is counted.
+    fn expr(&mut self, min_prec: u8) -> Result<Node, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::new(self.peek().col, "expression nested too deeply"));
+        }
+
+        self.depth += 1;
+        let node = self.climb(min_prec);
+        self.depth -= 1;
+        node
+    }
+
+    fn climb(&mut self, min_prec: u8) -> Result<Node, Error> {
+        let mut lhs = self.unary()?;
+
+        while let Some((op, prec, right)) = binary(&self.peek().tok) {
+            if prec < min_prec {
+                break;
+            }
+            let col = self.next().col;
+            let rhs = self.expr(if right { prec } else { prec + 1 })?;
+            lhs = Node::new(Expr::Binary(op, Box::new(lhs), Box::new(rhs)), col)?;
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Node, Error> {
+        if self.peek().tok == Tok::Sym("-") {
+            let col = self.next().col;
+            let operand = self.expr(NEG_PREC + 1)?;
+            return Node::new(Expr::Neg(Box::new(operand)), col);
+        }
+
+        let mut node = self.primary()?;
+        while self.peek().tok == Tok::Sym("!") {
+            let col = self.next().col;
+            node = Node::new(Expr::Fact(Box::new(node)), col)?;
+        }
+        Ok(node)
+    }
+
+    fn primary(&mut self) -> Result<Node, Error> {
+        let t = self.next();
+        let expr = match t.tok {
+            Tok::Num(n) => Expr::Num(n),
+            Tok::Ident(name) if self.peek().tok == Tok::Sym("(") => {
+                self.next();
+                let mut args = Vec::new();
+                if self.peek().tok != Tok::Sym(")") {
+                    args.push(self.expr(0)?);
+                    while self.peek().tok == Tok::Sym(",") {
+                        self.next();
+                        args.push(self.expr(0)?);
+                    }
+                }
+                self.expect(")")?;
+                Expr::Call(name, args)
+            }
+            Tok::Ident(name) => Expr::Var(name),
+            Tok::Sym("(") => {
+                let node = self.expr(0)?;
+                self.expect(")")?;
+                return Ok(node);
+            }
+            tok => return Err(Error::new(t.col, format!("unexpected {tok}"))),
+        };
+        Node::new(expr, t.col)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::token::tokenize;
+    use super::{Expr, Parser, Stmt};
+
+    /// Fully parenthesized form of the parsed line.
+    fn show(e: &Expr) -> String {
+        match e {
+            Expr::Num(n) => n.to_string(),
+            Expr::Var(s) => s.clone(),
+            Expr::Neg(x) => format!("(-{})", show(&x.expr)),
+            Expr::Fact(x) => format!("({}!)", show(&x.expr)),
+            Expr::Binary(op, a, b) => format!("({} {op} {})", show(&a.expr), show(&b.expr)),
+            Expr::Call(f, args) => {
+                let args = args.iter().map(|a| show(&a.expr)).collect::<Vec<_>>();
+                format!("{f}({})", args.join(", "))
+            }
+        }
+    }
+
+    fn parse(s: &str) -> Result<String, (usize, String)> {
+        let stmt = tokenize(s)
+            .and_then(|t| Parser::new(t).statement())
+            .map_err(|e| (e.col, e.msg))?;
+        Ok(match stmt {
+            Stmt::Assign(name, x) => format!("{name} = {}", show(&x.expr)),
+            Stmt::Expr(x) => show(&x.expr),
+        })
+    }
+
+    #[test]
+    fn precedence_test() {
+        fn case(s: &str, c: &str) {
+            assert_eq!(parse(s), Ok(c.to_string()), "{s}");
+        }
+
+        case("1 + 2 * 3", "(1 + (2 * 3))");
+        case("1 - 2 - 3", "((1 - 2) - 3)");
+        case("2 ^ 3 ^ 2", "(2 ^ (3 ^ 2))");
+        case("-2 ^ 2", "(-(2 ^ 2))");
+        case("-2 * 3", "((-2) * 3)");
+        case("2 ^ -1", "(2 ^ (-1))");
+        case("3! ^ 2", "((3!) ^ 2)");
+        case("(1 + 2) * 3", "((1 + 2) * 3)");
+        case("1 + 2 < 4 % 3", "((1 + 2) < (4 % 3))");
+        case("gcd(12, x + 1) * f()", "(gcd(12, (x + 1)) * f())");
+    }
+
+    #[test]
+    fn error_test() {
+        fn case(s: &str, col: usize, msg: &str) {
+            assert_eq!(parse(s), Err((col, msg.to_string())), "{s}");
+        }
+
+        case("", 1, "unexpected end of input");
+        case("1 +", 4, "unexpected end of input");
+        case("(1 + 2", 7, "expected `)`, found end of input");
+        case("1 2", 3, "unexpected `2`");
+        case("gcd(1 2)", 7, "expected `)`, found `2`");
+        case("* 3", 1, "unexpected `*`");
+        case("1 = 2", 3, "unexpected `=`");
+        case("x = y = 1", 7, "unexpected `=`");
+    }
+
+    #[test]
+    fn depth_test() {
+        let n = super::MAX_DEPTH - 1;
+        let s = format!("{}1{}", "(".repeat(n), ")".repeat(n));
+        assert_eq!(parse(&s), Ok("1".to_string()));
+
+        let s = format!("{}1{}", "(".repeat(n + 1), ")".repeat(n + 1));
+        assert_eq!(
+            parse(&s),
+            Err((n + 2, "expression nested too deeply".to_string()))
+        );
+        // A chain of `k` operators is a tree of height `k + 1`.
+        let s = "1+".repeat(n) + "1";
+        assert!(parse(&s).is_ok());
+        let s = "1+".repeat(n + 1) + "1";
+        assert_eq!(
+            parse(&s),
+            Err((2 * (n + 1), "expression nested too deeply".to_string()))
+        );
+        let s = "3".to_string() + &"!".repeat(n);
+        assert!(parse(&s).is_ok());
+
+        for s in [
+            "(".repeat(20_000),
+            "-".repeat(200_000),
+            "2^".repeat(5000) + "2",
+            "1+".repeat(50_000) + "1",
+            "1*2-".repeat(2000) + "1",
+            "3".to_string() + &"!".repeat(5000),
+            "gcd(".repeat(1000),
+        ] {
+            let e = parse(&s).unwrap_err();
+            assert_eq!(e.1, "expression nested too deeply");
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Splitting a line into numbers, names and symbols.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use super::Error;
+use crate::big_int::BigInt;
+
+/// Symbols, longest first so that `<=` is not read as `<` and `=`.
+const SYMBOLS: [&str; 17] = [
+    "==", "!=", "<=", ">=", "+", "-", "*", "/", "%", "^", "!", "(", ")", ",", "=", "<", ">",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Tok {
+    Num(BigInt),
+    Ident(String),
+    Sym(&'static str),
+    End,
+}
+
+impl Display for Tok {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tok::Num(n) => write!(f, "`{n}`"),
+            Tok::Ident(s) => write!(f, "`{s}`"),
+            Tok::Sym(s) => write!(f, "`{s}`"),
+            Tok::End => write!(f, "end of input"),
+        }
+    }
+}
+
+/// A token and the 1-based column of its first character.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Token {
+    pub(super) tok: Tok,
+    pub(super) col: usize,
+}
+
+/// Tokens of `line`, always ending with `Tok::End`.
+///
+/// A number is a digit followed by letters, digits and `_`, so `0xff` and
+/// `1_000` are single numbers; `,` separates function arguments instead.
+pub(super) fn tokenize(line: &str) -> Result<Vec<Token>, Error> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let col = i + 1;
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_alphanumeric() || c == '_' {
+            let len = chars[i..]
+                .iter()
+                .take_while(|&&c| c.is_alphanumeric() || c == '_')
+                .count();
+            let s = chars[i..i + len].iter().collect::<String>();
+            i += len;
+
+            let tok = if c.is_ascii_digit() {
+                let n = BigInt::from_str(&s)
+                    .map_err(|e| Error::new(col, format!("invalid number `{s}`: {e}")))?;
+                Tok::Num(n)
+            } else {
+                Tok::Ident(s)
+            };
+            tokens.push(Token { tok, col });
+            continue;
+        }
+
+        let rest = chars[i..].iter().take(2).collect::<String>();
+        let Some(sym) = SYMBOLS.into_iter().find(|s| rest.starts_with(s)) else {
+            return Err(Error::new(col, format!("unexpected character `{c}`")));
+        };
+        tokens.push(Token {
+            tok: Tok::Sym(sym),
+            col,
+        });
+        i += sym.len();
+    }
+
+    tokens.push(Token {
+        tok: Tok::End,
+        col: chars.len() + 1,
+    });
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{tokenize, Tok};
+    use crate::big_int::BigInt;
+
+    #[test]
+    fn tokenize_test() {
+        let tokens = tokenize(" x1 = 0xff^2! <= y").unwrap();
+        let toks = tokens
+            .iter()
+            .map(|t| (t.tok.clone(), t.col))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            toks,
+            [
+                (Tok::Ident("x1".to_string()), 2),
+                (Tok::Sym("="), 5),
+                (Tok::Num(BigInt::from(255)), 7),
+                (Tok::Sym("^"), 11),
+                (Tok::Num(BigInt::from(2)), 12),
+                (Tok::Sym("!"), 13),
+                (Tok::Sym("<="), 15),
+                (Tok::Ident("y".to_string()), 18),
+                (Tok::End, 19),
+            ]
+        );
+
+        let e = tokenize("1 + 2a").unwrap_err();
+        assert_eq!(
+            (e.col, e.msg.as_str()),
+            (
+                5,
+                "invalid number `2a`: number contains invalid character `a` at byte 1"
+            )
+        );
+        let e = tokenize("1 # 2").unwrap_err();
+        assert_eq!((e.col, e.msg.as_str()), (3, "unexpected character `#`"));
+    }
+}
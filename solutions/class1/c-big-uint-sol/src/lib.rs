@@ -0,0 +1,7 @@
+pub mod big_decimal;
+pub mod big_int;
+pub mod big_rational;
+pub mod big_uint;
+pub mod cli;
+pub mod expr;
+pub mod uint;
@@ -4,54 +4,165 @@
 //!
 //! ```sh
 //! > cargo run -p c-big-uint-sol
-//! <number> <operator> <number>
+//! <expression>
 //! <output>
+//! ...
 //! ```
 //!
+//! Each line is evaluated until the end of the input (`Ctrl-D`).
+//!
 //! ## Examples
 //!
 //! ```sh
 //! > cargo run -p c-big-uint-sol
 //! 100 + 100
 //! 200
+//! 100 - 250 * 2
+//! -400
+//! ```
+//!
+//! Expressions have `+ - * / % ^`, factorial `!`, parentheses, comparisons
+//! and the functions `gcd`, `sqrt` and `modpow`. Results can be kept in
+//! variables, and the last one is always available as `ans`.
+//!
+//! ```sh
+//! > cargo run -p c-big-uint-sol
+//! x = 2^100
+//! 1267650600228229401496703205376
+//! modpow(3, x, 1000007) == modpow(3, ans, 1000007)
+//! true
 //! ```
 //!
+//! Invalid input, like a division by zero, is reported on stderr with the
+//! column it was found at, and the next line is read as usual.
+//!
+//! ```sh
+//! > cargo run -p c-big-uint-sol
+//! 1 / 0
+//! error: column 3: division by zero
+//! ```
+//!
+//! ## Batch mode
+//!
+//! Expressions can also come from a file, one per line, or from `-e`
+//! arguments. Results can be printed as JSON lines or CSV, and in another
+//! base. The exit status is `1` if any line failed.
+//!
+//! ```sh
+//! > cargo run -p c-big-uint-sol -- -f json --base 16 -e "2^64 - 1" -e "1 / 0"
+//! {"expr":"2^64 - 1","result":"ffffffffffffffff","error":null}
+//! {"expr":"1 / 0","result":null,"error":"column 3: division by zero"}
+//! ```
+//!
+//! See `--help` for all options.
+//!
 //! ## How to test this code
 //!
 //! ```sh
 //! cargo test -p c-big-uint-sol
 //! ```
 
-use std::io;
-use std::io::BufRead;
-use std::str::FromStr;
+use std::io::{BufRead, IsTerminal, Write};
+use std::{env, fs, io, process};
 
-use crate::big_uint::BigUInt;
-
-mod big_uint;
+use c_big_uint_sol::big_uint;
+use c_big_uint_sol::cli::{Format, Options, Printer, USAGE};
+use c_big_uint_sol::expr::Env;
 
 fn main() {
-    let stdin = io::stdin();
-    let input = stdin.lock().lines().next().unwrap().unwrap();
-    let input = input.split(' ').collect::<Vec<_>>();
-
-    let a = BigUInt::from_str(input[0]).unwrap();
-    let b = BigUInt::from_str(input[2]).unwrap();
-
-    let c = match input[1] {
-        "+" => (&a + &b).to_string(),
-        "-" => (&a - &b).to_string(),
-        "*" => (&a * &b).to_string(),
-        "/" => (&a / &b).to_string(),
-        "%" => (&a % &b).to_string(),
-        "==" => (a == b).to_string(),
-        "!=" => (a != b).to_string(),
-        "<" => (a < b).to_string(),
-        ">" => (a > b).to_string(),
-        "<=" => (a <= b).to_string(),
-        ">=" => (a >= b).to_string(),
-        _ => return,
+    let opts = match Options::parse(env::args().skip(1)) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            process::exit(2);
+        }
     };
+    if opts.help {
+        println!("{USAGE}");
+        return;
+    }
+    big_uint::set_threads(opts.threads);
+
+    let mut session = Session {
+        env: Env::new(),
+        printer: Printer {
+            format: opts.format,
+            base: opts.base,
+        },
+        failed: false,
+    };
+    if let Some(header) = session.printer.header() {
+        println!("{header}");
+    }
+
+    if let Some(path) = &opts.file {
+        let text = fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("error: cannot read `{path}`: {e}");
+            process::exit(2);
+        });
+        for (i, line) in text.lines().enumerate() {
+            session.run(line, Some(i + 1), false);
+        }
+    } else if !opts.exprs.is_empty() {
+        for expr in &opts.exprs {
+            session.run(expr, None, false);
+        }
+    } else {
+        let stdin = io::stdin();
+        let interactive = stdin.is_terminal() && opts.format == Format::Plain;
+        let prompt = || {
+            if interactive {
+                print!("> ");
+                let _ = io::stdout().flush();
+            }
+        };
+
+        prompt();
+        for line in stdin.lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            session.run(&line, None, interactive);
+            prompt();
+        }
+
+        // A typo at the prompt is not a failure of the whole session.
+        if interactive {
+            return;
+        }
+    }
+
+    if session.failed {
+        process::exit(1);
+    }
+}
+
+/// Evaluates lines in one environment and prints their results.
+struct Session {
+    env: Env,
+    printer: Printer,
+    failed: bool,
+}
+
+impl Session {
+    fn run(&mut self, line: &str, line_no: Option<usize>, interactive: bool) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            return;
+        }
+
+        let result = self.env.eval_line(line);
+        if let Err(e) = &result {
+            self.failed = true;
+            if interactive {
+                // Point at the column, under the prompt and the input.
+                eprintln!("{}^", " ".repeat(e.col + 1));
+            }
+        }
 
-    println!("{c}");
+        match self.printer.record(line, line_no, &result) {
+            (s, true) => println!("{s}"),
+            (s, false) => eprintln!("{s}"),
+        }
+    }
 }
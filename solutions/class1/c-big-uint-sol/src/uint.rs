@@ -0,0 +1,449 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops;
+
+use crate::big_uint::{kernel, BigUInt, TryFromBigUIntError};
+
+/// Fixed-width unsigned integer of `LIMBS` 64-bit limbs
+///
+/// ## Example
+///
+/// ```
+/// let a = U256::MAX;
+/// assert_eq!(a.wrapping_add(U256::ONE), U256::ZERO);
+/// assert_eq!(a.checked_mul(U256::from(2u64)), None);
+/// assert_eq!(BigUInt::from(a), &(&BigUInt::one() << 256) - &BigUInt::one());
+/// ```
+///
+/// ## Implementation
+///
+/// The limbs are stored little-endian in an array, so the value lives on the
+/// stack and is `Copy`. The arithmetic runs the same limb kernels as
+/// `BigUInt`. The `+`, `-` and `*` operators panic on overflow; use the
+/// `wrapping_*`, `checked_*` and `overflowing_*` methods to handle it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct UInt<const LIMBS: usize> {
+    limbs: [u64; LIMBS],
+}
+
+pub type U128 = UInt<2>;
+pub type U256 = UInt<4>;
+pub type U512 = UInt<8>;
+
+impl<const LIMBS: usize> UInt<LIMBS> {
+    pub const BITS: usize = 64 * LIMBS;
+    pub const ZERO: Self = UInt { limbs: [0; LIMBS] };
+    pub const MAX: Self = UInt {
+        limbs: [u64::MAX; LIMBS],
+    };
+    pub const ONE: Self = {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = 1;
+        UInt { limbs }
+    };
+
+    /// Number from little-endian limbs.
+    pub const fn from_limbs(limbs: [u64; LIMBS]) -> Self {
+        UInt { limbs }
+    }
+
+    /// Little-endian limbs.
+    pub const fn as_limbs(&self) -> &[u64; LIMBS] {
+        &self.limbs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&x| x == 0)
+    }
+
+    /// `self + rhs` modulo `2^BITS`, and whether it overflowed.
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let mut limbs = self.limbs;
+        let carry = kernel::add_assign(&mut limbs, &rhs.limbs);
+        (UInt { limbs }, carry)
+    }
+
+    /// `self - rhs` modulo `2^BITS`, and whether it overflowed.
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let mut limbs = self.limbs;
+        let borrow = kernel::sub_assign(&mut limbs, &rhs.limbs);
+        (UInt { limbs }, borrow)
+    }
+
+    /// `self * rhs` modulo `2^BITS`, and whether it overflowed.
+    ///
+    /// Only the partial products below `2^BITS` are accumulated; any limb
+    /// that would land above it means the full product did not fit.
+    pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+        let mut limbs = [0; LIMBS];
+        let mut overflow = false;
+
+        for (i, &y) in rhs.limbs.iter().enumerate() {
+            if y == 0 {
+                continue;
+            }
+            let n = LIMBS - i;
+            let carry = kernel::mul_limb_add(&mut limbs[i..], &self.limbs[..n], y);
+            overflow |= carry != 0 || self.limbs[n..].iter().any(|&x| x != 0);
+        }
+
+        (UInt { limbs }, overflow)
+    }
+
+    pub fn wrapping_add(self, rhs: Self) -> Self {
+        self.overflowing_add(rhs).0
+    }
+
+    pub fn wrapping_sub(self, rhs: Self) -> Self {
+        self.overflowing_sub(rhs).0
+    }
+
+    pub fn wrapping_mul(self, rhs: Self) -> Self {
+        self.overflowing_mul(rhs).0
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (x, false) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (x, false) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_mul(rhs) {
+            (x, false) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Quotient and remainder, or `None` if `rhs` is zero.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let (q, r) = U128::from(100u64).checked_div_rem(U128::from(7u64)).unwrap();
+    /// assert_eq!((q, r), (U128::from(14u64), U128::from(2u64)));
+    /// ```
+    pub fn checked_div_rem(self, rhs: Self) -> Option<(Self, Self)> {
+        let n = kernel::trimmed_len(&rhs.limbs);
+        if n == 0 {
+            return None;
+        }
+        if self < rhs {
+            return Some((Self::ZERO, self));
+        }
+
+        if n == 1 {
+            let mut q = self.limbs;
+            let r = kernel::div_rem_limb(&mut q, rhs.limbs[0]);
+            return Some((UInt { limbs: q }, Self::from(r)));
+        }
+        Some(self.knuth_d(rhs, n))
+    }
+
+    /// Algorithm D for a divisor of `n >= 2` limbs, like `BigUInt` division
+    /// but without allocating: the running remainder takes one limb of the
+    /// dividend at a time, so every buffer fits in `LIMBS` limbs.
+    fn knuth_d(self, rhs: Self, n: usize) -> (Self, Self) {
+        // D1: normalize so that the top bit of the divisor is set.
+        let s = rhs.limbs[n - 1].leading_zeros();
+        let mut v = rhs.limbs;
+        kernel::shl_bits(&mut v[..n], s);
+        let mut u = self.limbs;
+        let top = kernel::shl_bits(&mut u, s);
+
+        let (v1, v2) = (v[n - 1] as u128, v[n - 2] as u128);
+        let mut q = [0; LIMBS];
+        let mut r = [0; LIMBS];
+        let mut t = [0; LIMBS];
+        r[0] = top;
+
+        for j in (0..LIMBS).rev() {
+            // Bring down the next limb; the part of the dividend being
+            // divided is `hi` followed by `r[..n]`.
+            let hi = r[n - 1];
+            r.copy_within(..n - 1, 1);
+            r[0] = u[j];
+
+            // D3: estimate the quotient digit from the top two limbs.
+            let num = ((hi as u128) << 64) | r[n - 1] as u128;
+            let mut qhat = num / v1;
+            let mut rhat = num % v1;
+
+            while qhat > u64::MAX as u128 || qhat * v2 > ((rhat << 64) | r[n - 2] as u128) {
+                qhat -= 1;
+                rhat += v1;
+                if rhat > u64::MAX as u128 {
+                    break;
+                }
+            }
+
+            // D4: multiply and subtract.
+            t[..n].copy_from_slice(&v[..n]);
+            let t_hi = kernel::mul_limb(&mut t[..n], qhat as u64);
+            let borrow = kernel::sub_assign(&mut r[..n], &t[..n]);
+
+            // D6: the estimate was one too large, add the divisor back.
+            if (hi as u128) < t_hi as u128 + borrow as u128 {
+                qhat -= 1;
+                kernel::add_assign(&mut r[..n], &v[..n]);
+            }
+
+            q[j] = qhat as u64;
+        }
+
+        // D8: unnormalize the remainder.
+        kernel::shr_bits(&mut r[..n], s);
+        (UInt { limbs: q }, UInt { limbs: r })
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_div_rem(rhs).map(|(q, _)| q)
+    }
+
+    pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+        self.checked_div_rem(rhs).map(|(_, r)| r)
+    }
+
+    /// Number from at most `LIMBS` little-endian limbs.
+    fn from_slice(x: &[u64]) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[..x.len()].copy_from_slice(x);
+        UInt { limbs }
+    }
+}
+
+impl<const LIMBS: usize> Default for UInt<LIMBS> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const LIMBS: usize> From<u64> for UInt<LIMBS> {
+    fn from(x: u64) -> Self {
+        Self::from_slice(&[x])
+    }
+}
+
+impl<const LIMBS: usize> From<UInt<LIMBS>> for BigUInt {
+    fn from(x: UInt<LIMBS>) -> Self {
+        BigUInt::from_u64_digits(&x.limbs)
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<&BigUInt> for UInt<LIMBS> {
+    type Error = TryFromBigUIntError;
+
+    /// The same number, or `OutOfRange` if it needs more than `LIMBS` limbs.
+    fn try_from(x: &BigUInt) -> Result<Self, Self::Error> {
+        let digits = x.to_u64_digits();
+        if digits.len() > LIMBS {
+            return Err(TryFromBigUIntError::OutOfRange);
+        }
+        Ok(Self::from_slice(&digits))
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for UInt<LIMBS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const LIMBS: usize> Ord for UInt<LIMBS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        kernel::cmp(&self.limbs, &other.limbs)
+    }
+}
+
+impl<const LIMBS: usize> Display for UInt<LIMBS> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&BigUInt::from(*self), f)
+    }
+}
+
+macro_rules! uint_op_impl {
+    ($op:ident, $f:ident, $op_assign:ident, $f_assign:ident, $checked:ident, $msg:literal) => {
+        impl<const LIMBS: usize> ops::$op for UInt<LIMBS> {
+            type Output = Self;
+
+            fn $f(self, rhs: Self) -> Self::Output {
+                self.$checked(rhs).expect($msg)
+            }
+        }
+
+        impl<const LIMBS: usize> ops::$op_assign for UInt<LIMBS> {
+            fn $f_assign(&mut self, rhs: Self) {
+                *self = ops::$op::$f(*self, rhs);
+            }
+        }
+    };
+}
+
+uint_op_impl!(
+    Add,
+    add,
+    AddAssign,
+    add_assign,
+    checked_add,
+    "attempt to add with overflow"
+);
+uint_op_impl!(
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    checked_sub,
+    "attempt to subtract with overflow"
+);
+uint_op_impl!(
+    Mul,
+    mul,
+    MulAssign,
+    mul_assign,
+    checked_mul,
+    "attempt to multiply with overflow"
+);
+uint_op_impl!(
+    Div,
+    div,
+    DivAssign,
+    div_assign,
+    checked_div,
+    "division by zero"
+);
+uint_op_impl!(
+    Rem,
+    rem,
+    RemAssign,
+    rem_assign,
+    checked_rem,
+    "division by zero"
+);
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::big_uint::{BigUInt, TryFromBigUIntError, XorShift64};
+
+    use super::{UInt, U128, U256, U512};
+
+    fn big(s: &str) -> BigUInt {
+        BigUInt::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn consts_test() {
+        assert_eq!(U256::BITS, 256);
+        assert_eq!(BigUInt::from(U128::MAX), BigUInt::from(u128::MAX));
+        assert_eq!(BigUInt::from(U512::ONE), BigUInt::one());
+        assert_eq!(U256::default(), U256::ZERO);
+        assert!(U256::ZERO < U256::ONE && U256::ONE < U256::MAX);
+        assert_eq!(UInt::<1>::MAX.to_string(), u64::MAX.to_string());
+    }
+
+    #[test]
+    fn convert_test() {
+        let a = big("0x1234567890abcdef_fedcba0987654321_0011223344556677");
+        let b = U256::try_from(&a).unwrap();
+        assert_eq!(b.as_limbs()[3], 0);
+        assert_eq!(BigUInt::from(b), a);
+        assert_eq!(U128::try_from(&a), Err(TryFromBigUIntError::OutOfRange));
+        assert_eq!(U128::try_from(&BigUInt::zero()), Ok(U128::ZERO));
+    }
+
+    #[test]
+    fn arith_test() {
+        let mut rng = XorShift64::new(31);
+        let modulus = &BigUInt::one() << 256;
+
+        for bits in [0, 1, 64, 100, 128, 200, 255, 256] {
+            for _ in 0..10 {
+                let a = BigUInt::random_bits(bits, &mut rng);
+                let b = BigUInt::random_bits(256 - bits, &mut rng);
+                let (x, y) = (U256::try_from(&a).unwrap(), U256::try_from(&b).unwrap());
+
+                let sum = &a + &b;
+                assert_eq!(BigUInt::from(x.wrapping_add(y)), &sum % &modulus);
+                assert_eq!(x.checked_add(y).is_none(), sum >= modulus);
+
+                let prod = &a * &b;
+                assert_eq!(BigUInt::from(x.wrapping_mul(y)), &prod % &modulus);
+                assert_eq!(x.checked_mul(y).is_none(), prod >= modulus);
+
+                let diff = &(&a + &modulus) - &b;
+                assert_eq!(BigUInt::from(x.wrapping_sub(y)), &diff % &modulus);
+                assert_eq!(x.checked_sub(y).is_none(), a < b);
+
+                if !b.is_zero() {
+                    assert_eq!(BigUInt::from(x / y), &a / &b);
+                    assert_eq!(BigUInt::from(x % y), &a % &b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn div_test() {
+        fn case(a: [u64; 4], b: [u64; 4]) {
+            let (x, y) = (U256::from_limbs(a), U256::from_limbs(b));
+            let (q, r) = x.checked_div_rem(y).unwrap();
+            let (a, b) = (BigUInt::from(x), BigUInt::from(y));
+            assert_eq!(
+                (BigUInt::from(q), BigUInt::from(r)),
+                (&a / &b, &a % &b),
+                "{a} / {b}"
+            );
+        }
+
+        const H: u64 = 1 << 63;
+        const M: u64 = u64::MAX;
+
+        case([0, 0, H, H - 1], [1, 0, H, 0]);
+        case([3, 0, H, 0], [1, 0, H >> 2, 0]);
+        case([0, 0, H, H - 1], [M, H - 1, 0, 0]);
+        case([M; 4], [M, M, M, 0]);
+        case([M; 4], [M; 4]);
+        case([M; 4], [1, 0, 0, H]);
+        case([0, 0, 0, 1], [M, M, 0, 0]);
+        case([0, M, M, 0], [1, M, 0, 0]);
+        case([7, 7, 7, 7], [7, 7, 7, 0]);
+        case([5, 0, 0, 0], [M, M, 0, 0]);
+        case([M; 4], [3, 0, 0, 0]);
+    }
+
+    #[test]
+    fn overflow_test() {
+        assert_eq!(U256::MAX.overflowing_add(U256::ONE), (U256::ZERO, true));
+        assert_eq!(U256::ZERO.overflowing_sub(U256::ONE), (U256::MAX, true));
+        assert_eq!(U256::MAX.overflowing_mul(U256::MAX), (U256::ONE, true));
+
+        // 2^128 * 2^127 fits, 2^128 * 2^128 does not.
+        let a = U256::from_limbs([0, 0, 1, 0]);
+        let b = U256::from_limbs([0, 1 << 63, 0, 0]);
+        assert_eq!(a.checked_mul(b), Some(U256::from_limbs([0, 0, 0, 1 << 63])));
+        assert_eq!(a.checked_mul(a), None);
+
+        let mut c = U128::from(7u64);
+        c *= U128::from(6u64);
+        c -= U128::from(2u64);
+        c /= U128::from(8u64);
+        c %= U128::from(3u64);
+        assert_eq!(c, U128::from(2u64));
+        assert_eq!(U128::ONE.checked_div(U128::ZERO), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to add with overflow")]
+    fn add_overflow_test() {
+        let _ = U512::MAX + U512::ONE;
+    }
+}